@@ -0,0 +1,230 @@
+use crate::config::CaseRule;
+use regex::Regex;
+
+/// A letter case, named as in commitlint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    Lower,
+    Upper,
+    Camel,
+    Kebab,
+    Pascal,
+    Sentence,
+    Snake,
+    Start,
+}
+
+impl Case {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "lowercase" | "lower-case" => Some(Case::Lower),
+            "uppercase" | "upper-case" => Some(Case::Upper),
+            "camel-case" => Some(Case::Camel),
+            "kebab-case" => Some(Case::Kebab),
+            "pascal-case" => Some(Case::Pascal),
+            "sentence-case" => Some(Case::Sentence),
+            "snake-case" => Some(Case::Snake),
+            "start-case" => Some(Case::Start),
+            _ => None,
+        }
+    }
+}
+
+/// A case requirement as written in the config, e.g. `kebab-case` or `never upper-case`.
+#[derive(Debug, Clone, Copy)]
+struct CaseSpec<'a> {
+    name: &'a str,
+    case: Case,
+    negated: bool,
+}
+
+impl<'a> CaseSpec<'a> {
+    /// Parse `spec`; unknown case names are skipped.
+    fn parse(spec: &'a str) -> Option<Self> {
+        let spec = spec.trim();
+        let (negated, name) = match spec.strip_prefix("never ") {
+            Some(name) => (true, name.trim()),
+            None => (false, spec),
+        };
+        Case::from_name(name).map(|case| Self {
+            name,
+            case,
+            negated,
+        })
+    }
+}
+
+/// Classifies text by case, skipping code spans, acronyms and proper nouns.
+pub struct CaseChecker<'a> {
+    rule: &'a CaseRule,
+}
+
+impl<'a> CaseChecker<'a> {
+    pub fn new(rule: &'a CaseRule) -> Self {
+        Self { rule }
+    }
+
+    /// Check `text` against `specs`.
+    ///
+    /// Text must match at least one of the plain specs and none of the `never` ones.
+    /// Returns what the text must (not) be when it fails, e.g. `must be lowercase`.
+    pub fn check(&self, text: &str, specs: &[String]) -> Option<String> {
+        let specs: Vec<CaseSpec> = specs.iter().filter_map(|s| CaseSpec::parse(s)).collect();
+
+        if let Some(spec) = specs
+            .iter()
+            .filter(|spec| spec.negated)
+            .find(|spec| self.matches(text, spec.case) == Some(true))
+        {
+            return Some(format!("must not be {}", spec.name));
+        }
+
+        let required: Vec<&CaseSpec> = specs.iter().filter(|spec| !spec.negated).collect();
+        let results: Vec<Option<bool>> = required
+            .iter()
+            .map(|spec| self.matches(text, spec.case))
+            .collect();
+        if required.is_empty()
+            || results.contains(&Some(true))
+            || results.iter().all(Option::is_none)
+        {
+            return None;
+        }
+
+        match required.as_slice() {
+            [spec] => Some(format!("must be {}", spec.name)),
+            _ => {
+                let names: Vec<&str> = required.iter().map(|spec| spec.name).collect();
+                Some(format!("must match one of: {}", names.join(", ")))
+            }
+        }
+    }
+
+    /// Whether `text` is in `case`, or `None` when it has no cased letters to judge.
+    pub fn matches(&self, text: &str, case: Case) -> Option<bool> {
+        let spans = Regex::new(r#"`[^`]*`|"[^"]*""#).unwrap();
+        let text = spans.replace_all(text, " ");
+        let all_words: Vec<&str> = text.split_whitespace().collect();
+        let words: Vec<&str> = all_words
+            .iter()
+            .copied()
+            .filter(|word| !self.is_ignored(word))
+            .collect();
+
+        if !words.iter().any(|word| word.chars().any(is_cased)) {
+            return None;
+        }
+
+        match case {
+            Case::Lower => Some(!words.iter().any(|w| w.chars().any(char::is_uppercase))),
+            Case::Upper => Some(!words.iter().any(|w| w.chars().any(char::is_lowercase))),
+            Case::Sentence => {
+                // Only the first letter matters, as in commitlint's `upperFirst`
+                let first = all_words.first().filter(|word| !self.is_ignored(word))?;
+                let c = first.chars().next().filter(|&c| is_cased(c))?;
+                Some(c.is_uppercase())
+            }
+            Case::Start => Some(words.iter().all(|word| {
+                word.chars()
+                    .find(|c| c.is_alphabetic())
+                    .is_none_or(|c| !c.is_lowercase())
+            })),
+            Case::Camel | Case::Pascal | Case::Kebab | Case::Snake => {
+                let lower = r"[\p{Ll}\p{Lm}\p{Lo}\p{N}]";
+                let upper = r"[\p{Lu}\p{Lt}]";
+                let pattern = match case {
+                    Case::Camel => format!("^{l}+(?:{u}{l}+)*{u}?$", l = lower, u = upper),
+                    Case::Pascal => format!("^{u}{l}*(?:{u}{l}+)*{u}?$", l = lower, u = upper),
+                    Case::Kebab => format!("^{l}+(?:-{l}+)*$", l = lower),
+                    _ => format!("^{l}+(?:_{l}+)*$", l = lower),
+                };
+                Some(Regex::new(&pattern).unwrap().is_match(&words.join(" ")))
+            }
+        }
+    }
+
+    /// Whether `word` is a configured acronym (optionally plural) or proper noun.
+    fn is_ignored(&self, word: &str) -> bool {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+        let singular = word
+            .strip_suffix("'s")
+            .or_else(|| word.strip_suffix('s'))
+            .unwrap_or(word);
+
+        self.rule
+            .acronyms
+            .iter()
+            .any(|acronym| acronym == word || acronym == singular)
+            || self.rule.proper_nouns.iter().any(|noun| noun == word)
+    }
+}
+
+fn is_cased(c: char) -> bool {
+    c.is_lowercase() || c.is_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let rule = CaseRule::default();
+        let checker = CaseChecker::new(&rule);
+
+        assert_eq!(checker.matches("v2-api", Case::Kebab), Some(true));
+        assert_eq!(checker.matches("v2_api", Case::Kebab), Some(false));
+        assert_eq!(checker.matches("parseHttpUrl", Case::Camel), Some(true));
+        assert_eq!(checker.matches("ParseUrl", Case::Camel), Some(false));
+        assert_eq!(checker.matches("ParseUrl", Case::Pascal), Some(true));
+        assert_eq!(checker.matches("Add a feature", Case::Sentence), Some(true));
+        assert_eq!(
+            checker.matches("add a feature", Case::Sentence),
+            Some(false)
+        );
+        assert_eq!(checker.matches("Add A Feature", Case::Start), Some(true));
+        assert_eq!(checker.matches("日本語のサポート", Case::Sentence), None);
+        assert_eq!(checker.matches("données ÉTÉ", Case::Lower), Some(false));
+    }
+
+    #[test]
+    fn test_ignores_code_spans_acronyms_and_proper_nouns() {
+        let rule = CaseRule {
+            acronyms: vec!["API".to_string(), "HTTP".to_string()],
+            proper_nouns: vec!["GitHub".to_string()],
+        };
+        let checker = CaseChecker::new(&rule);
+
+        assert_eq!(
+            checker.matches("add `FooBar` to HTTP APIs", Case::Lower),
+            Some(true)
+        );
+        assert_eq!(
+            checker.matches("support GitHub login", Case::Lower),
+            Some(true)
+        );
+        assert_eq!(checker.matches("API client", Case::Sentence), None);
+    }
+
+    #[test]
+    fn test_check_with_negation() {
+        let rule = CaseRule::default();
+        let checker = CaseChecker::new(&rule);
+        let specs = vec![
+            "never sentence-case".to_string(),
+            "never upper-case".to_string(),
+        ];
+
+        assert_eq!(checker.check("add a feature", &specs), None);
+        assert_eq!(
+            checker.check("Add a feature", &specs).as_deref(),
+            Some("must not be sentence-case")
+        );
+        assert_eq!(
+            checker
+                .check("Add", &["lowercase".to_string(), "kebab-case".to_string()])
+                .as_deref(),
+            Some("must match one of: lowercase, kebab-case")
+        );
+    }
+}
@@ -1,14 +1,42 @@
+use crate::config::Parser;
+use crate::gitmoji;
+use crate::reference;
+pub use crate::reference::Reference;
 use regex::Regex;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub struct ConventionalCommit {
+    /// Emoji or `:shortcode:` preceding the type, as written
+    pub emoji: Option<String>,
     pub r#type: String,
+    /// The scope as written, e.g. `api,cli`
     pub scope: Option<String>,
+    /// The scope split on `parser.scope_delimiters`, e.g. `["api", "cli"]`
+    pub scopes: Vec<String>,
+    /// Whether the commit is breaking, by either marker
     pub breaking: bool,
+    /// Whether the header carries the `!` marker
+    pub breaking_marker: bool,
+    /// The `BREAKING CHANGE` footer's description, if the footer is present
+    pub breaking_description: Option<String>,
     pub subject: String,
     pub body: Option<String>,
     pub footer: Option<HashMap<String, String>>,
+    /// Issue references found in the subject, body and footer
+    pub references: Vec<Reference>,
+}
+
+/// Commits git or the user creates outside the Conventional Commits flow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitKind {
+    Merge,
+    Revert,
+    Fixup,
+    Squash,
+    Amend,
+    Initial,
+    Regular,
 }
 
 #[derive(Debug, Clone)]
@@ -17,9 +45,94 @@ pub struct CommitMessage {
     pub header: String,
     pub body: Option<String>,
     pub footer: Option<String>,
+    /// Whether the body is separated from the header by a blank line
+    pub blank_before_body: bool,
+    /// Whether the footer is separated from what precedes it by a blank line
+    pub blank_before_footer: bool,
+}
+
+/// Characters git picks from with `core.commentChar = auto`.
+const AUTO_COMMENT_CHARS: &str = "#;@!$%^&|:";
+
+/// Strip what git removes from an edited message before committing: lines starting
+/// with `comment` (`core.commentChar`) and everything below the `--verbose` scissors line.
+pub fn cleanup_message(raw: &str, comment: &str) -> String {
+    let mut lines = Vec::new();
+
+    for line in raw.lines() {
+        if is_scissors(line, comment) {
+            break;
+        }
+        if !line.starts_with(comment) {
+            lines.push(line.trim_end());
+        }
+    }
+
+    lines.join("\n").trim().to_string()
+}
+
+/// Split an edited message file into the message and what git appended to it: the
+/// trailing blank and comment lines, and everything from the scissors line on.
+pub fn split_comments<'a>(raw: &'a str, comment: &str) -> (&'a str, &'a str) {
+    let mut offset = 0;
+    let mut trailing: Option<usize> = None;
+
+    for line in raw.split_inclusive('\n') {
+        let text = line.trim_end_matches(['\n', '\r']);
+        if is_scissors(text, comment) {
+            return raw.split_at(trailing.unwrap_or(offset));
+        }
+        if text.trim().is_empty() || text.starts_with(comment) {
+            trailing.get_or_insert(offset);
+        } else {
+            trailing = None;
+        }
+        offset += line.len();
+    }
+
+    raw.split_at(trailing.unwrap_or(raw.len()))
+}
+
+/// The comment character of an edited message file under `core.commentChar = auto`:
+/// the one the scissors line or the final comment lines start with, else `#`.
+pub fn detect_comment_char(raw: &str) -> String {
+    let last = raw
+        .lines()
+        .find(|line| line.contains(" ------------------------ >8 ------------------------"))
+        .or_else(|| raw.lines().rev().find(|line| !line.trim().is_empty()));
+
+    last.and_then(|line| line.chars().next())
+        .filter(|c| AUTO_COMMENT_CHARS.contains(*c))
+        .unwrap_or('#')
+        .to_string()
+}
+
+/// Whether `line` is the scissors line git writes above the diff in `commit --verbose`.
+fn is_scissors(line: &str, comment: &str) -> bool {
+    line.strip_prefix(comment)
+        .is_some_and(|rest| rest == " ------------------------ >8 ------------------------")
+}
+
+/// Append a trailer line, joining an existing trailer block or starting a new one.
+pub fn append_trailer(message: &str, trailer: &str) -> String {
+    let message = message.trim_end();
+    let msg = CommitMessage::from_str(message);
+    let last_line = message.lines().last().unwrap_or_default();
+
+    let ends_with_trailers = msg
+        .footer
+        .as_deref()
+        .is_some_and(|footer| footer.lines().last() == Some(last_line));
+
+    if ends_with_trailers {
+        format!("{}\n{}\n", message, trailer)
+    } else {
+        format!("{}\n\n{}\n", message, trailer)
+    }
 }
 
 impl CommitMessage {
+    #[allow(clippy::should_implement_trait)] // infallible, unlike FromStr
     pub fn from_str(msg: &str) -> Self {
         let lines: Vec<&str> = msg.lines().collect();
         let header = lines.first().map(|s| s.to_string()).unwrap_or_default();
@@ -27,16 +140,28 @@ impl CommitMessage {
         let mut body_lines = Vec::new();
         let mut footer_lines = Vec::new();
         let mut in_footer = false;
+        let mut blank_before_footer = false;
+        let footer_token =
+            Regex::new(r"^(?:BREAKING[ -]CHANGE:|[A-Z][A-Za-z]*(?:-[A-Za-z]+)*:)").unwrap();
+        // Like git interpret-trailers, "Fixes #12" is only a trailer when it is the whole
+        // line and in the last paragraph, so "Issue #42 was caused by..." stays in the body
+        let reference_token = Regex::new(r"^[A-Z][A-Za-z]*(?:-[A-Za-z]+)* #\S+$").unwrap();
+        let last_line = lines.iter().rposition(|line| !line.trim().is_empty());
+        let last_paragraph = last_line
+            .and_then(|end| lines[..end].iter().rposition(|line| line.trim().is_empty()))
+            .map_or(1, |blank| blank + 1);
 
         for (i, line) in lines.iter().enumerate().skip(1) {
             if i == 1 && line.trim().is_empty() {
                 continue; // Skip blank line after header
             }
 
-            // Footer starts with BREAKING CHANGE or a token like "Closes:", "Fixes:", etc.
-            if line.starts_with("BREAKING CHANGE:")
-                || Regex::new(r"^[A-Z][a-z]+(?:-[A-Z][a-z]+)*:").unwrap().is_match(line) {
+            // Footer starts with BREAKING CHANGE or a token like "Closes:", "Fixes #12", etc.
+            let is_token = footer_token.is_match(line)
+                || (i >= last_paragraph && reference_token.is_match(line.trim_end()));
+            if !in_footer && is_token {
                 in_footer = true;
+                blank_before_footer = lines[i - 1].trim().is_empty();
             }
 
             if in_footer {
@@ -63,18 +188,122 @@ impl CommitMessage {
             header,
             body,
             footer,
+            blank_before_body: lines.get(1).is_none_or(|line| line.trim().is_empty()),
+            blank_before_footer,
+        }
+    }
+
+    pub fn kind(&self) -> CommitKind {
+        let merge = Regex::new(
+            r#"^(?:Merge (?:pull request|branch|remote-tracking branch|tag|commit|'|")|Merged .+ into |Automatic merge|Auto-merged )"#,
+        )
+        .unwrap();
+
+        if merge.is_match(&self.header) {
+            CommitKind::Merge
+        } else if self.reverted_header().is_some() {
+            CommitKind::Revert
+        } else if self.header.starts_with("fixup! ") {
+            CommitKind::Fixup
+        } else if self.header.starts_with("squash! ") {
+            CommitKind::Squash
+        } else if self.header.starts_with("amend! ") {
+            CommitKind::Amend
+        } else if self.header.trim().eq_ignore_ascii_case("initial commit") {
+            CommitKind::Initial
+        } else {
+            CommitKind::Regular
+        }
+    }
+
+    /// The original header quoted by a `Revert "..."` (or `Reapply "..."`) header.
+    pub fn reverted_header(&self) -> Option<&str> {
+        let revert = Regex::new(r#"^(?:Revert|Reapply) "(.*)"$"#).unwrap();
+        let mut header = self.header.as_str();
+
+        // Reverting a revert nests the quotes; unwrap down to the original header
+        while let Some(caps) = revert.captures(header) {
+            header = caps.get(1).unwrap().as_str();
         }
+
+        if header == self.header {
+            None
+        } else {
+            Some(header)
+        }
+    }
+
+    /// Footer trailers as `(token, value)` pairs, in order.
+    ///
+    /// Accepts both `Token: value` and `Token #value`; lines that don't start a new
+    /// trailer continue the previous value.
+    pub fn trailers(&self) -> Vec<(String, String)> {
+        let trailer =
+            Regex::new(r"^(?P<token>BREAKING[ -]CHANGE|[A-Za-z][A-Za-z-]*)(?::\s*| (?P<value>#))")
+                .unwrap();
+        let mut trailers: Vec<(String, String)> = Vec::new();
+
+        for line in self.footer.iter().flat_map(|footer| footer.lines()) {
+            if let Some(caps) = trailer.captures(line) {
+                let value_start = caps
+                    .name("value")
+                    .map_or(caps.get(0).unwrap().end(), |m| m.start());
+                trailers.push((
+                    caps["token"].to_string(),
+                    line[value_start..].trim().to_string(),
+                ));
+            } else if let Some((_, value)) = trailers.last_mut() {
+                value.push('\n');
+                value.push_str(line);
+            }
+        }
+
+        for (_, value) in trailers.iter_mut() {
+            *value = value.trim_end().to_string();
+        }
+
+        trailers
     }
 
-    pub fn parse_conventional(&self, pattern: &str) -> anyhow::Result<ConventionalCommit> {
-        let re = Regex::new(pattern)?;
+    /// Values of the `Signed-off-by` trailers.
+    pub fn sign_offs(&self) -> Vec<String> {
+        self.trailers()
+            .into_iter()
+            .filter(|(token, _)| token == "Signed-off-by")
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    /// `Co-authored-by` trailers, matched case-insensitively so misspellings can be reported.
+    pub fn co_author_trailers(&self) -> Vec<(String, String)> {
+        self.trailers()
+            .into_iter()
+            .filter(|(token, _)| token.eq_ignore_ascii_case("Co-authored-by"))
+            .collect()
+    }
+
+    pub fn parse_conventional(&self, parser: &Parser) -> anyhow::Result<ConventionalCommit> {
+        let re = Regex::new(&parser.pattern)?;
+
+        // A leading gitmoji is split off unless the pattern captures it itself
+        let captures_emoji = re.capture_names().any(|name| name == Some("emoji"));
+        let (emoji, header) = if captures_emoji {
+            (None, self.header.as_str())
+        } else {
+            gitmoji::split_prefix(&self.header)
+        };
 
-        if let Some(caps) = re.captures(&self.header) {
+        if let Some(caps) = re.captures(header) {
+            let emoji = caps.name("emoji").map(|m| m.as_str()).or(emoji);
             let r#type = caps.name("type")
                 .map(|m| m.as_str().to_string())
                 .ok_or_else(|| anyhow::anyhow!("Missing 'type' in commit message"))?;
 
             let scope = caps.name("scope").map(|m| m.as_str().to_string());
+            let scopes = scope
+                .as_deref()
+                .map(|scope| split_scopes(scope, &parser.scope_delimiters))
+                .unwrap_or_default();
             let breaking = caps.name("breaking").is_some();
             let subject = caps.name("subject")
                 .map(|m| m.as_str().to_string())
@@ -82,27 +311,46 @@ impl CommitMessage {
 
             // Parse footer for breaking changes and other metadata
             let mut footer_map = HashMap::new();
-            if let Some(ref footer) = self.footer {
-                for line in footer.lines() {
-                    if line.starts_with("BREAKING CHANGE:") {
-                        footer_map.insert("BREAKING CHANGE".to_string(),
-                            line.strip_prefix("BREAKING CHANGE:").unwrap_or("").trim().to_string());
-                    } else if let Some((key, value)) = line.split_once(':') {
-                        footer_map.insert(key.trim().to_string(), value.trim().to_string());
-                    }
-                }
+            for (token, value) in self.trailers() {
+                let token = if token == "BREAKING-CHANGE" {
+                    "BREAKING CHANGE".to_string()
+                } else {
+                    token
+                };
+                footer_map.insert(token, value);
             }
 
             // Check footer for breaking change indicator
-            let breaking_from_footer = footer_map.contains_key("BREAKING CHANGE");
+            let breaking_description = footer_map.get("BREAKING CHANGE").cloned();
+
+            // References may appear anywhere after the type and scope
+            let referenced_text = [
+                Some(subject.as_str()),
+                self.body.as_deref(),
+                self.footer.as_deref(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("\n");
+            let references = reference::parse_references(
+                &referenced_text,
+                &parser.issue_prefixes,
+                &parser.reference_actions,
+            );
 
             Ok(ConventionalCommit {
+                emoji: emoji.map(str::to_string),
                 r#type,
                 scope,
-                breaking: breaking || breaking_from_footer,
+                scopes,
+                breaking: breaking || breaking_description.is_some(),
+                breaking_marker: breaking,
+                breaking_description,
                 subject,
                 body: self.body.clone(),
                 footer: if footer_map.is_empty() { None } else { Some(footer_map) },
+                references,
             })
         } else {
             anyhow::bail!("Commit message does not match conventional commit format")
@@ -110,6 +358,19 @@ impl CommitMessage {
     }
 }
 
+/// Split `scope` on any of `delimiters`, trimming whitespace around each scope.
+fn split_scopes(scope: &str, delimiters: &[String]) -> Vec<String> {
+    let mut scopes = vec![scope.to_string()];
+    for delimiter in delimiters.iter().filter(|d| !d.is_empty()) {
+        scopes = scopes
+            .iter()
+            .flat_map(|scope| scope.split(delimiter.as_str()))
+            .map(str::to_string)
+            .collect();
+    }
+    scopes.iter().map(|s| s.trim().to_string()).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,16 +384,165 @@ mod tests {
     #[test]
     fn test_parse_with_scope() {
         let msg = CommitMessage::from_str("feat(api): add endpoint");
-        let pattern = r"^(?P<type>\w+)(?:\((?P<scope>[^)]+)\))?(?P<breaking>!)?:\s(?P<subject>.*)$";
-        let commit = msg.parse_conventional(pattern).unwrap();
+        let commit = msg.parse_conventional(&Parser::default()).unwrap();
         assert_eq!(commit.r#type, "feat");
         assert_eq!(commit.scope, Some("api".to_string()));
+        assert_eq!(commit.scopes, ["api"]);
+
+        let parser = Parser {
+            scope_delimiters: vec![",".to_string(), "|".to_string()],
+            ..Parser::default()
+        };
+        let msg = CommitMessage::from_str("fix(api, cli|net/http): retry");
+        let commit = msg.parse_conventional(&parser).unwrap();
+        assert_eq!(commit.scopes, ["api", "cli", "net/http"]);
+    }
+
+    #[test]
+    fn test_parse_with_gitmoji() {
+        let commit = CommitMessage::from_str("✨ feat(api): add endpoint")
+            .parse_conventional(&Parser::default())
+            .unwrap();
+        assert_eq!(commit.emoji.as_deref(), Some("✨"));
+        assert_eq!(commit.r#type, "feat");
+
+        let commit = CommitMessage::from_str(":bug: fix: handle overflow")
+            .parse_conventional(&Parser::default())
+            .unwrap();
+        assert_eq!(commit.emoji.as_deref(), Some(":bug:"));
+        assert_eq!(commit.subject, "handle overflow");
+    }
+
+    #[test]
+    fn test_parse_breaking_markers() {
+        let commit = CommitMessage::from_str("feat(api)!: drop v1")
+            .parse_conventional(&Parser::default())
+            .unwrap();
+        assert!(commit.breaking && commit.breaking_marker);
+        assert_eq!(commit.breaking_description, None);
+
+        let commit = CommitMessage::from_str("feat: drop v1\n\nBREAKING-CHANGE: v1 is gone")
+            .parse_conventional(&Parser::default())
+            .unwrap();
+        assert!(commit.breaking && !commit.breaking_marker);
+        assert_eq!(commit.breaking_description.as_deref(), Some("v1 is gone"));
+    }
+
+    #[test]
+    fn test_commit_kind() {
+        let kind = |msg: &str| CommitMessage::from_str(msg).kind();
+        assert_eq!(kind("Merge branch 'main' into feature"), CommitKind::Merge);
+        assert_eq!(
+            kind("Merge pull request #12 from org/branch"),
+            CommitKind::Merge
+        );
+        assert_eq!(kind("Revert \"feat: add x\""), CommitKind::Revert);
+        assert_eq!(kind("fixup! feat: add x"), CommitKind::Fixup);
+        assert_eq!(kind("squash! feat: add x"), CommitKind::Squash);
+        assert_eq!(kind("amend! feat: add x"), CommitKind::Amend);
+        assert_eq!(kind("Initial commit"), CommitKind::Initial);
+        assert_eq!(kind("feat: merge sort"), CommitKind::Regular);
+    }
+
+    #[test]
+    fn test_reverted_header_unwraps_nested_reverts() {
+        let msg = CommitMessage::from_str("Revert \"Revert \"feat: add x\"\"");
+        assert_eq!(msg.reverted_header(), Some("feat: add x"));
+    }
+
+    #[test]
+    fn test_trailers_and_references() {
+        let msg = CommitMessage::from_str(
+            "fix: handle #7 overflow\n\nBody text.\n\nCloses #12\nReviewed-by: Jane\n  Doe",
+        );
+        assert_eq!(
+            msg.trailers(),
+            [
+                ("Closes".to_string(), "#12".to_string()),
+                ("Reviewed-by".to_string(), "Jane\n  Doe".to_string()),
+            ]
+        );
+
+        let commit = msg.parse_conventional(&Parser::default()).unwrap();
+        let refs: Vec<(&str, Option<&str>)> = commit
+            .references
+            .iter()
+            .map(|r| (r.issue.as_str(), r.action.as_deref()))
+            .collect();
+        assert_eq!(refs, [("7", None), ("12", Some("Closes"))]);
+    }
+
+    #[test]
+    fn test_hash_trailers_only_in_last_paragraph() {
+        let msg = CommitMessage::from_str(
+            "fix: handle reset\n\nIssue #42 was caused by a stale cache.\nClear it on reset.",
+        );
+        assert_eq!(
+            msg.body.as_deref(),
+            Some("Issue #42 was caused by a stale cache.\nClear it on reset.")
+        );
+        assert_eq!(msg.footer, None);
+
+        let msg = CommitMessage::from_str(
+            "fix: handle reset\n\nRefs #41\n\nClear the cache.\n\nFixes #42",
+        );
+        assert_eq!(msg.body.as_deref(), Some("Refs #41\n\nClear the cache.\n"));
+        assert_eq!(msg.footer.as_deref(), Some("Fixes #42"));
+    }
+
+    #[test]
+    fn test_cleanup_message() {
+        let raw = "feat: add x\n\nBody.\n# Please enter the commit message\n\n# ------------------------ >8 ------------------------\ndiff --git a/x b/x\n";
+        assert_eq!(cleanup_message(raw, "#"), "feat: add x\n\nBody.");
+        assert_eq!(
+            split_comments(raw, "#"),
+            ("feat: add x\n\nBody.\n", "# Please enter the commit message\n\n# ------------------------ >8 ------------------------\ndiff --git a/x b/x\n")
+        );
+
+        // With another comment character, `#` lines are part of the message
+        let raw = "fix: quote paths\n\n#123 reported this.\n\n; Please enter the commit message\n";
+        assert_eq!(detect_comment_char(raw), ";");
+        assert_eq!(
+            cleanup_message(raw, ";"),
+            "fix: quote paths\n\n#123 reported this."
+        );
+        assert_eq!(
+            split_comments(raw, ";").1,
+            "\n; Please enter the commit message\n"
+        );
+
+        // A diff line resembling a scissors line does not cut the message short
+        let raw = "feat: add x\n\n+# ------------------------ >8 ------------------------\n";
+        assert_eq!(cleanup_message(raw, "#"), raw.trim());
+    }
+
+    #[test]
+    fn test_append_trailer() {
+        let sign_off = "Signed-off-by: Jane Doe <jane@example.com>";
+        assert_eq!(
+            append_trailer("feat: add x", sign_off),
+            format!("feat: add x\n\n{}\n", sign_off)
+        );
+        assert_eq!(
+            append_trailer("feat: add x\n\nCloses #1\n", sign_off),
+            format!("feat: add x\n\nCloses #1\n{}\n", sign_off)
+        );
     }
 
     #[test]
     fn test_parse_with_body() {
         let msg = CommitMessage::from_str("feat: add feature\n\nThis is the body");
         assert_eq!(msg.body, Some("This is the body".to_string()));
+        assert!(msg.blank_before_body);
     }
-}
 
+    #[test]
+    fn test_parse_detects_missing_blank_lines() {
+        let msg = CommitMessage::from_str("feat: add feature\nbody\nCloses: #1");
+        assert!(!msg.blank_before_body);
+        assert!(!msg.blank_before_footer);
+
+        let msg = CommitMessage::from_str("feat: add feature\n\nbody\n\nCloses: #1");
+        assert!(msg.blank_before_footer);
+    }
+}
@@ -1,5 +1,13 @@
+use crate::commit::CommitKind;
+pub use crate::gitmoji::EmojiFormat;
+use crate::secrets;
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -9,6 +17,159 @@ pub struct Config {
     pub parser: Parser,
     #[serde(default)]
     pub ignores: Vec<String>,
+    #[serde(default)]
+    pub default_ignores: DefaultIgnores,
+    #[serde(default)]
+    pub branch: BranchConfig,
+    /// Rule overlays applied on matching branches, CI or hooks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub when: Vec<When>,
+    #[serde(default)]
+    pub nested: NestedConfig,
+}
+
+/// How `commitlint.toml` files in subdirectories apply to commits touching them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NestedConfig {
+    /// Look for nested configs covering the files a commit touches
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// What to do when a commit touches files covered by different nested configs
+    #[serde(default)]
+    pub span: SpanPolicy,
+}
+
+/// Policy for commits spanning several nested config areas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpanPolicy {
+    /// Apply every area's config; lists are combined (widening allowlists such as
+    /// `scope.enum`) and other values from later areas (in path order) win
+    #[default]
+    Merge,
+    /// Use the root config only
+    Root,
+    /// Use the root config and fail with `config-span`
+    Reject,
+}
+
+/// Which configs were used for a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Applied {
+    /// The root config; no nested config covers the touched files
+    Root,
+    /// The root config with these nested configs merged over it
+    Nested(Vec<PathBuf>),
+    /// The root config, because the touched files span these nested configs
+    Spanning(Vec<PathBuf>),
+}
+
+impl Applied {
+    /// A note for the report naming the configs used, if not just the root one.
+    pub fn describe(&self) -> Option<String> {
+        let list = |paths: &[PathBuf]| {
+            let names: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+            names.join(", ")
+        };
+        match self {
+            Applied::Root => None,
+            Applied::Nested(paths) => Some(format!("using {}", list(paths))),
+            Applied::Spanning(paths) => {
+                Some(format!("spans {}; using the root config", list(paths)))
+            }
+        }
+    }
+}
+
+/// A `[[when]]` block: settings that apply only where all of its conditions hold.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct When {
+    /// Branch globs, e.g. `release/*`; `*` does not cross `/`, `**` does
+    #[serde(default)]
+    pub branch: Vec<String>,
+    /// Match only in CI (`true`) or only outside it (`false`)
+    #[serde(default)]
+    pub ci: Option<bool>,
+    /// Match only when run by this git hook, e.g. `commit-msg`
+    #[serde(default)]
+    pub hook: Option<String>,
+    /// Rule settings merged over `[rules]`
+    #[serde(default)]
+    pub rules: toml::Table,
+    /// Replaces the top-level `ignores`
+    #[serde(default)]
+    pub ignores: Option<Vec<String>>,
+}
+
+impl When {
+    pub fn matches(&self, context: &RunContext) -> bool {
+        let branch = self.branch.is_empty()
+            || context.branch.as_deref().is_some_and(|branch| {
+                self.branch
+                    .iter()
+                    .any(|pattern| glob_matches(pattern, branch))
+            });
+        let ci = self.ci.is_none_or(|ci| ci == context.ci);
+        let hook = self.hook.is_none() || self.hook == context.hook;
+        branch && ci && hook
+    }
+}
+
+/// Where a check runs, for matching `[[when]]` blocks.
+#[derive(Debug, Clone, Default)]
+pub struct RunContext {
+    pub branch: Option<String>,
+    pub ci: bool,
+    pub hook: Option<String>,
+}
+
+impl RunContext {
+    /// A context with `ci` taken from the `CI` environment variable most CI services set.
+    pub fn detect(branch: Option<String>, hook: Option<&str>) -> Self {
+        let ci = std::env::var("CI").is_ok_and(|value| !value.is_empty() && value != "false");
+        Self {
+            branch,
+            ci,
+            hook: hook.map(str::to_string),
+        }
+    }
+}
+
+/// Match `text` against a glob where `*` matches within a path segment, `**` across
+/// segments and `?` a single character.
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                regex.push_str(".*");
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex::Regex::new(&regex).is_ok_and(|regex| regex.is_match(text))
+}
+
+/// Built-in classes of commits that skip validation, each enabled by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefaultIgnores {
+    #[serde(default = "default_true")]
+    pub merge: bool,
+    #[serde(default = "default_true")]
+    pub revert: bool,
+    #[serde(default = "default_true")]
+    pub fixup: bool,
+    #[serde(default = "default_true")]
+    pub squash: bool,
+    #[serde(default = "default_true")]
+    pub amend: bool,
+    #[serde(default = "default_true")]
+    pub initial: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,7 +178,8 @@ pub struct Rules {
     pub r#type: TypeRule,
     #[serde(default = "default_scope_rule")]
     pub scope: ScopeRule,
-    #[serde(default = "default_subject_case")]
+    /// Case requirements for the subject, empty to allow any case
+    #[serde(default)]
     pub subject_case: Vec<String>,
     #[serde(default = "default_subject_empty")]
     pub subject_empty: bool,
@@ -31,26 +193,572 @@ pub struct Rules {
     pub body_leading_blank: bool,
     #[serde(default = "default_body_max_line_length")]
     pub body_max_line_length: usize,
+    /// Require a body
+    #[serde(default)]
+    pub body_empty: bool,
+    #[serde(default)]
+    pub body_min_length: usize,
+    /// Maximum body length, 0 for no limit
+    #[serde(default)]
+    pub body_max_length: usize,
+    #[serde(default)]
+    pub body_case: Vec<String>,
+    /// Character the body must not end with, empty to allow any
+    #[serde(default)]
+    pub body_full_stop: String,
     #[serde(default = "default_footer_leading_blank")]
     pub footer_leading_blank: bool,
     #[serde(default = "default_footer_max_line_length")]
     pub footer_max_line_length: usize,
+    /// Require a footer
+    #[serde(default)]
+    pub footer_empty: bool,
+    #[serde(default)]
+    pub footer_min_length: usize,
+    /// Maximum footer length, 0 for no limit
+    #[serde(default)]
+    pub footer_max_length: usize,
+    /// How header and line lengths are measured
+    #[serde(default)]
+    pub length_unit: LengthUnit,
+    #[serde(default)]
+    pub revert: RevertRule,
+    #[serde(default)]
+    pub references: ReferencesRule,
+    #[serde(default)]
+    pub signed_off_by: SignedOffByRule,
+    #[serde(default)]
+    pub co_authored_by: CoAuthoredByRule,
+    #[serde(default)]
+    pub gitmoji: GitmojiRule,
+    #[serde(default)]
+    pub case: CaseRule,
+    #[serde(default)]
+    pub breaking: BreakingRule,
+    #[serde(default)]
+    pub subject_imperative: ImperativeRule,
+    #[serde(default)]
+    pub no_secrets: NoSecretsRule,
+    /// Partial rule tables applied to commits of a given type, e.g. `[rules.overrides.feat]`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub overrides: HashMap<String, toml::Table>,
+}
+
+impl Rules {
+    /// The rules for a commit, with every matching `[rules.overrides.<key>]` applied.
+    ///
+    /// Keys are a type (`feat`), a scope (`(api)`) or both (`feat(api)`), looked up for
+    /// each of the commit's scopes; more specific overrides win.
+    pub fn for_commit(
+        &self,
+        commit_type: &str,
+        scopes: &[String],
+    ) -> anyhow::Result<Cow<'_, Rules>> {
+        let overrides = self.matching_overrides(commit_type, scopes);
+        if overrides.is_empty() {
+            return Ok(Cow::Borrowed(self));
+        }
+
+        let tables: Vec<&toml::Table> = overrides.into_iter().map(|(_, table)| table).collect();
+        self.with_overrides(&tables).map(Cow::Owned)
+    }
+
+    /// Check that `[rules.overrides.<key>]` deserializes into valid rules.
+    pub fn check_override(&self, key: &str) -> anyhow::Result<()> {
+        match self.overrides.get(key) {
+            Some(table) => self.with_overrides(&[table])?.check(),
+            None => Ok(()),
+        }
+    }
+
+    /// Check the settings that only fail once used, such as regex patterns.
+    fn check(&self) -> anyhow::Result<()> {
+        secrets::Scanner::new(&self.no_secrets)
+            .context("Invalid pattern in [rules.no_secrets.patterns]")?;
+        Ok(())
+    }
+
+    /// How the override that sets `rule` for this commit is named in messages, e.g.
+    /// `type feat`, if one does.
+    ///
+    /// Which keys set a rule is listed in its `explain::RuleDoc`, e.g.
+    /// `rules.header_max_length` and `rules.length_unit` for `header-max-length`.
+    pub fn override_label(
+        &self,
+        commit_type: &str,
+        scopes: &[String],
+        rule: &str,
+    ) -> Option<String> {
+        let doc = crate::explain::find(rule)?;
+        self.matching_overrides(commit_type, scopes)
+            .into_iter()
+            .rev()
+            .find(|(_, table)| doc.set_by(table))
+            .map(|(key, _)| match key.strip_prefix('(') {
+                Some(scope) => format!("scope {}", scope.trim_end_matches(')')),
+                None if key.contains('(') => key.clone(),
+                None => format!("type {}", key),
+            })
+    }
+
+    /// Overrides matching a commit, least specific first and then in scope order.
+    fn matching_overrides(
+        &self,
+        commit_type: &str,
+        scopes: &[String],
+    ) -> Vec<(String, &toml::Table)> {
+        let mut keys = vec![commit_type.to_string()];
+        keys.extend(scopes.iter().map(|scope| format!("({})", scope)));
+        keys.extend(
+            scopes
+                .iter()
+                .map(|scope| format!("{}({})", commit_type, scope)),
+        );
+
+        keys.into_iter()
+            .filter_map(|key| self.overrides.get(&key).map(|table| (key, table)))
+            .collect()
+    }
+
+    /// These rules with `overlays` merged over them, in order.
+    pub fn overlay(&self, overlays: &[&toml::Table]) -> anyhow::Result<Rules> {
+        let mut rules = toml::Table::try_from(self)?;
+        for overlay in overlays {
+            merge_tables(&mut rules, overlay);
+        }
+        Ok(toml::Value::Table(rules).try_into()?)
+    }
+
+    fn with_overrides(&self, overrides: &[&toml::Table]) -> anyhow::Result<Rules> {
+        let base = Rules {
+            overrides: HashMap::new(),
+            ..self.clone()
+        };
+        base.overlay(overrides)
+    }
+}
+
+/// Every config in `chains`, in the order they are applied.
+fn configs_used(chains: &[Vec<PathBuf>]) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for path in chains.iter().flatten() {
+        if !paths.contains(path) {
+            paths.push(path.clone());
+        }
+    }
+    paths
+}
+
+/// Recursively overlay `overlay` onto `base`, so nested tables are merged key by key.
+fn merge_tables(base: &mut toml::Table, overlay: &toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                merge_tables(base, overlay)
+            }
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Like `merge_tables`, but arrays present in both are combined instead of replaced.
+///
+/// Used for commits spanning several nested configs, so a list such as `scope.enum`
+/// allows what any of the areas allows; `nested.span = "reject"` avoids the widening.
+fn combine_tables(base: &mut toml::Table, overlay: &toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                combine_tables(base, overlay)
+            }
+            (Some(toml::Value::Array(base)), toml::Value::Array(overlay)) => {
+                for item in overlay {
+                    if !base.contains(item) {
+                        base.push(item.clone());
+                    }
+                }
+            }
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Unit the length rules count in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LengthUnit {
+    /// UTF-8 bytes
+    Bytes,
+    /// Unicode scalar values
+    #[default]
+    Chars,
+    /// User-perceived characters (extended grapheme clusters)
+    Graphemes,
+    /// Terminal columns, counting East Asian wide characters as 2
+    Width,
+}
+
+impl LengthUnit {
+    pub fn measure(self, text: &str) -> usize {
+        match self {
+            LengthUnit::Bytes => text.len(),
+            LengthUnit::Chars => text.chars().count(),
+            LengthUnit::Graphemes => text.graphemes(true).count(),
+            LengthUnit::Width => text.width(),
+        }
+    }
+
+    /// The unit's name in error messages.
+    pub fn name(self) -> &'static str {
+        match self {
+            LengthUnit::Bytes => "bytes",
+            LengthUnit::Chars => "characters",
+            LengthUnit::Graphemes => "grapheme clusters",
+            LengthUnit::Width => "columns",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TypeRule {
+    /// Allowed types, as names or `{ name = "feat", description = "..." }` tables
     #[serde(default = "default_type_enum")]
-    pub r#enum: Vec<String>,
+    pub r#enum: Vec<EnumEntry>,
     #[serde(default = "default_type_case")]
     pub case: String,
+    /// Other names for a type, e.g. `feature = "feat"`, rewritten by the commit-msg hook
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+    /// Types still accepted with a warning, mapped to their replacement (or `""`)
+    #[serde(default)]
+    pub deprecated: HashMap<String, String>,
+}
+
+impl TypeRule {
+    /// The entry for `name`, if it is an allowed type.
+    pub fn get(&self, name: &str) -> Option<&EnumEntry> {
+        self.r#enum.iter().find(|entry| entry.name == name)
+    }
+
+    /// The types to show in listings and error messages, in configured order.
+    pub fn listed(&self) -> impl Iterator<Item = &EnumEntry> {
+        self.r#enum.iter().filter(|entry| !entry.hidden)
+    }
+
+    /// Whether `enum` is the built-in list of types.
+    pub(crate) fn is_default_enum(&self) -> bool {
+        self.r#enum == default_type_enum()
+    }
+}
+
+/// An allowed type or scope, written either as a plain name or as a table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "EntryRepr", into = "EntryRepr")]
+pub struct EnumEntry {
+    pub name: String,
+    /// When to use it, shown in `type-enum` errors and `cargo commitlint types`
+    pub description: Option<String>,
+    /// Changelog heading for commits of this type, e.g. `Features`
+    pub changelog_section: Option<String>,
+    /// Gitmoji for this type, also accepted by the `gitmoji-type` rule
+    pub emoji: Option<String>,
+    /// Still accepted, but left out of listings and error messages
+    pub hidden: bool,
+}
+
+impl EnumEntry {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            changelog_section: None,
+            emoji: None,
+            hidden: false,
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_changelog_section(mut self, section: impl Into<String>) -> Self {
+        self.changelog_section = Some(section.into());
+        self
+    }
+}
+
+impl From<&str> for EnumEntry {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl AsRef<str> for EnumEntry {
+    fn as_ref(&self) -> &str {
+        &self.name
+    }
+}
+
+/// How an [`EnumEntry`] is written in TOML: a bare name unless it has details.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum EntryRepr {
+    Name(String),
+    Table(EntryTable),
+}
+
+#[derive(Serialize, Deserialize)]
+struct EntryTable {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    changelog_section: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    emoji: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    hidden: bool,
+}
+
+impl From<EntryRepr> for EnumEntry {
+    fn from(repr: EntryRepr) -> Self {
+        match repr {
+            EntryRepr::Name(name) => Self::new(name),
+            EntryRepr::Table(table) => Self {
+                name: table.name,
+                description: table.description,
+                changelog_section: table.changelog_section,
+                emoji: table.emoji,
+                hidden: table.hidden,
+            },
+        }
+    }
+}
+
+impl From<EnumEntry> for EntryRepr {
+    fn from(entry: EnumEntry) -> Self {
+        if entry.description.is_none()
+            && entry.changelog_section.is_none()
+            && entry.emoji.is_none()
+            && !entry.hidden
+        {
+            return EntryRepr::Name(entry.name);
+        }
+        EntryRepr::Table(EntryTable {
+            name: entry.name,
+            description: entry.description,
+            changelog_section: entry.changelog_section,
+            emoji: entry.emoji,
+            hidden: entry.hidden,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScopeRule {
+    /// Allowed scopes; `net/*` allows any scope one level below `net`, `net/**` any depth
     #[serde(default = "default_scope_enum")]
-    pub r#enum: Vec<String>,
+    pub r#enum: Vec<EnumEntry>,
+    /// Case each `/`-separated segment of a scope must be in
     #[serde(default = "default_scope_case")]
     pub case: String,
+    /// Maximum number of scopes in a header; 0 disables the check
+    #[serde(default)]
+    pub max_count: usize,
+    /// `never` requires a scope, `always` forbids one; unset leaves it optional
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub empty: Option<ScopeEmpty>,
+    /// Per-type settings replacing `enum` and `empty` for commits of that type
+    #[serde(default)]
+    pub types: HashMap<String, TypeScopeRule>,
+    /// Other names for a scope, rewritten by the commit-msg hook
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+    /// Scopes still accepted with a warning, mapped to their replacement (or `""`)
+    #[serde(default)]
+    pub deprecated: HashMap<String, String>,
+}
+
+impl ScopeRule {
+    /// The allowed scopes for `commit_type`, and whether they are specific to it.
+    pub fn enum_for(&self, commit_type: &str) -> (&[EnumEntry], bool) {
+        match self.types.get(commit_type).and_then(|t| t.r#enum.as_ref()) {
+            Some(scopes) => (scopes, true),
+            None => (&self.r#enum, false),
+        }
+    }
+
+    pub fn empty_for(&self, commit_type: &str) -> Option<ScopeEmpty> {
+        self.types
+            .get(commit_type)
+            .and_then(|t| t.empty)
+            .or(self.empty)
+    }
+}
+
+/// Whether a scope may be empty, with commitlint's `scope-empty` semantics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScopeEmpty {
+    /// The scope must be empty
+    Always,
+    /// The scope must not be empty
+    Never,
+}
+
+/// Scope settings for one type under `[rules.scope.types.<type>]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TypeScopeRule {
+    /// Allowed scopes for this type; unset falls back to `rules.scope.enum`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#enum: Option<Vec<EnumEntry>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub empty: Option<ScopeEmpty>,
+}
+
+/// Words every case rule skips.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaseRule {
+    /// Acronyms such as `API` or `HTTP`, also skipped in plural form (`APIs`)
+    #[serde(default)]
+    pub acronyms: Vec<String>,
+    /// Proper nouns such as `GitHub` or `macOS`
+    #[serde(default)]
+    pub proper_nouns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BreakingRule {
+    /// Require a `BREAKING CHANGE:` footer whenever the header uses `!`
+    #[serde(default)]
+    pub require_footer: bool,
+    /// Require the `!` marker whenever a `BREAKING CHANGE:` footer is present
+    #[serde(default)]
+    pub require_marker: bool,
+    /// Minimum length of the `BREAKING CHANGE:` description
+    #[serde(default)]
+    pub description_min_length: usize,
+    /// Types that may not be breaking, e.g. `docs` or `test`
+    #[serde(default)]
+    pub forbidden_types: Vec<String>,
+    /// Reject every breaking change, e.g. on maintenance branches
+    #[serde(default)]
+    pub forbidden: bool,
+    /// Scopes breaking changes are allowed in; empty allows any
+    #[serde(default)]
+    pub allowed_scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImperativeRule {
+    /// Require the subject to start with a verb in the imperative mood ("add", not "added")
+    #[serde(default)]
+    pub enabled: bool,
+    /// First words that are never flagged
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// First words that are always flagged
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoSecretsRule {
+    /// Reject messages containing credentials such as API tokens or private keys
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Also flag long random-looking strings
+    #[serde(default)]
+    pub entropy: bool,
+    /// Minimum Shannon entropy, in bits per character, of a flagged string
+    #[serde(default = "default_entropy_threshold")]
+    pub entropy_threshold: f64,
+    /// Minimum length of a flagged string
+    #[serde(default = "default_entropy_min_length")]
+    pub entropy_min_length: usize,
+    /// Additional detectors as `name = "regex"`
+    #[serde(default)]
+    pub patterns: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RevertRule {
+    /// Validate the header quoted by `Revert "..."` instead of ignoring the commit
+    #[serde(default)]
+    pub validate_original_header: bool,
+    /// Require a `This reverts commit <sha>` line
+    #[serde(default)]
+    pub require_commit_reference: bool,
+}
+
+impl RevertRule {
+    pub fn enabled(&self) -> bool {
+        self.validate_original_header || self.require_commit_reference
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReferencesRule {
+    /// Require at least one issue reference on every commit
+    #[serde(default)]
+    pub required: bool,
+    /// Require an issue reference on commits of these types only
+    #[serde(default)]
+    pub required_types: Vec<String>,
+    /// Check that action trailers (`Closes: ...`) only list valid references
+    #[serde(default)]
+    pub validate_syntax: bool,
+}
+
+impl ReferencesRule {
+    pub fn required_for(&self, commit_type: &str) -> bool {
+        self.required || self.required_types.iter().any(|t| t == commit_type)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SignedOffByRule {
+    /// Require a `Signed-off-by:` trailer (Developer Certificate of Origin)
+    #[serde(default)]
+    pub required: bool,
+    /// In the commit-msg hook, append a sign-off from `git config user.name/user.email`
+    #[serde(default)]
+    pub autofix: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoAuthoredByRule {
+    /// Check `Co-authored-by:` trailers for spelling, `Name <email>` format, duplicates
+    /// and self-co-authoring
+    #[serde(default)]
+    pub validate: bool,
+    /// Allowlist or `.mailmap` file listing the emails co-authors may use
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowlist: Option<std::path::PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitmojiRule {
+    /// Require an emoji or `:shortcode:` before the type
+    #[serde(default)]
+    pub required: bool,
+    /// Reject headers that start with an emoji
+    #[serde(default)]
+    pub forbidden: bool,
+    /// Require the emoji to be one of those listed for the type in `types`
+    #[serde(default)]
+    pub match_type: bool,
+    /// Emoji allowed for each type, in either form
+    #[serde(default = "default_gitmoji_types")]
+    pub types: HashMap<String, Vec<String>>,
+    /// Form the emoji must be written in; the commit-msg hook rewrites the other form
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<EmojiFormat>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +767,46 @@ pub struct Parser {
     pub pattern: String,
     #[serde(default = "default_parser_correspondence")]
     pub correspondence: HashMap<String, String>,
+    /// Prefixes that introduce an issue reference, e.g. `#`, `GH-`, `PROJ-`
+    #[serde(default = "default_issue_prefixes")]
+    pub issue_prefixes: Vec<String>,
+    /// Keywords that act on a reference, e.g. `Closes #12`
+    #[serde(default = "default_reference_actions")]
+    pub reference_actions: Vec<String>,
+    /// Separators between several scopes, e.g. `,` for `feat(api,cli): ...`
+    #[serde(default = "default_scope_delimiters")]
+    pub scope_delimiters: Vec<String>,
+}
+
+/// Branch-name rules for `cargo commitlint branch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchConfig {
+    /// Regex branch names must match; its `type` group is checked against `types`
+    #[serde(default = "default_branch_pattern")]
+    pub pattern: String,
+    /// Allowed values of the `type` group; empty uses `rules.type.enum`
+    #[serde(default)]
+    pub types: Vec<String>,
+    /// Branches that are never checked, by exact name or by a prefix ending in `/`
+    #[serde(default = "default_branch_allow")]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub min_length: usize,
+    /// Maximum length in characters; 0 disables the check
+    #[serde(default)]
+    pub max_length: usize,
+    /// Also check pushed branches from a pre-push hook written by `install`
+    #[serde(default)]
+    pub pre_push: bool,
+}
+
+impl BranchConfig {
+    /// Whether `name` is exempt from the branch rules.
+    pub fn allows(&self, name: &str) -> bool {
+        self.allow.iter().any(|allowed| {
+            allowed == name || (allowed.ends_with('/') && name.starts_with(allowed.as_str()))
+        })
+    }
 }
 
 impl Default for Config {
@@ -67,6 +815,83 @@ impl Default for Config {
             rules: default_rules(),
             parser: Parser::default(),
             ignores: Vec::new(),
+            default_ignores: DefaultIgnores::default(),
+            branch: BranchConfig::default(),
+            when: Vec::new(),
+            nested: NestedConfig::default(),
+        }
+    }
+}
+
+impl Default for NestedConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            span: SpanPolicy::default(),
+        }
+    }
+}
+
+impl Default for BranchConfig {
+    fn default() -> Self {
+        Self {
+            pattern: default_branch_pattern(),
+            types: Vec::new(),
+            allow: default_branch_allow(),
+            min_length: 0,
+            max_length: 0,
+            pre_push: false,
+        }
+    }
+}
+
+impl Default for DefaultIgnores {
+    fn default() -> Self {
+        Self {
+            merge: true,
+            revert: true,
+            fixup: true,
+            squash: true,
+            amend: true,
+            initial: true,
+        }
+    }
+}
+
+impl DefaultIgnores {
+    pub fn ignores(&self, kind: CommitKind) -> bool {
+        match kind {
+            CommitKind::Merge => self.merge,
+            CommitKind::Revert => self.revert,
+            CommitKind::Fixup => self.fixup,
+            CommitKind::Squash => self.squash,
+            CommitKind::Amend => self.amend,
+            CommitKind::Initial => self.initial,
+            CommitKind::Regular => false,
+        }
+    }
+}
+
+impl Default for GitmojiRule {
+    fn default() -> Self {
+        Self {
+            required: false,
+            forbidden: false,
+            match_type: false,
+            types: default_gitmoji_types(),
+            format: None,
+        }
+    }
+}
+
+impl Default for NoSecretsRule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            entropy: false,
+            entropy_threshold: default_entropy_threshold(),
+            entropy_min_length: default_entropy_min_length(),
+            patterns: HashMap::new(),
         }
     }
 }
@@ -76,6 +901,9 @@ impl Default for Parser {
         Self {
             pattern: default_parser_pattern(),
             correspondence: default_parser_correspondence(),
+            issue_prefixes: default_issue_prefixes(),
+            reference_actions: default_reference_actions(),
+            scope_delimiters: default_scope_delimiters(),
         }
     }
 }
@@ -84,15 +912,34 @@ fn default_rules() -> Rules {
     Rules {
         r#type: default_type_rule(),
         scope: default_scope_rule(),
-        subject_case: default_subject_case(),
+        subject_case: Vec::new(),
         subject_empty: default_subject_empty(),
         subject_full_stop: default_subject_full_stop(),
         header_max_length: default_header_max_length(),
         header_min_length: default_header_min_length(),
         body_leading_blank: default_body_leading_blank(),
         body_max_line_length: default_body_max_line_length(),
+        body_empty: false,
+        body_min_length: 0,
+        body_max_length: 0,
+        body_case: Vec::new(),
+        body_full_stop: String::new(),
         footer_leading_blank: default_footer_leading_blank(),
         footer_max_line_length: default_footer_max_line_length(),
+        footer_empty: false,
+        footer_min_length: 0,
+        footer_max_length: 0,
+        length_unit: LengthUnit::default(),
+        revert: RevertRule::default(),
+        references: ReferencesRule::default(),
+        signed_off_by: SignedOffByRule::default(),
+        co_authored_by: CoAuthoredByRule::default(),
+        gitmoji: GitmojiRule::default(),
+        case: CaseRule::default(),
+        breaking: BreakingRule::default(),
+        subject_imperative: ImperativeRule::default(),
+        no_secrets: NoSecretsRule::default(),
+        overrides: HashMap::new(),
     }
 }
 
@@ -100,6 +947,8 @@ fn default_type_rule() -> TypeRule {
     TypeRule {
         r#enum: default_type_enum(),
         case: default_type_case(),
+        aliases: HashMap::new(),
+        deprecated: HashMap::new(),
     }
 }
 
@@ -107,30 +956,48 @@ fn default_scope_rule() -> ScopeRule {
     ScopeRule {
         r#enum: default_scope_enum(),
         case: default_scope_case(),
+        max_count: 0,
+        empty: None,
+        types: HashMap::new(),
+        aliases: HashMap::new(),
+        deprecated: HashMap::new(),
     }
 }
 
-fn default_type_enum() -> Vec<String> {
-    vec![
-        "build".to_string(),
-        "chore".to_string(),
-        "ci".to_string(),
-        "docs".to_string(),
-        "feat".to_string(),
-        "fix".to_string(),
-        "perf".to_string(),
-        "refactor".to_string(),
-        "revert".to_string(),
-        "style".to_string(),
-        "test".to_string(),
+fn default_type_enum() -> Vec<EnumEntry> {
+    [
+        ("build", "Changes to the build system or dependencies"),
+        ("chore", "Maintenance that touches neither source nor tests"),
+        ("ci", "Changes to CI configuration and scripts"),
+        ("docs", "Documentation only changes"),
+        ("feat", "A new feature"),
+        ("fix", "A bug fix"),
+        ("perf", "A change that improves performance"),
+        ("refactor", "A change that is neither a fix nor a feature"),
+        ("revert", "Reverts a previous commit"),
+        ("style", "Formatting and whitespace, no change in meaning"),
+        ("test", "Adding missing tests or correcting existing ones"),
     ]
+    .iter()
+    .map(|(name, description)| {
+        let entry = EnumEntry::new(*name).with_description(*description);
+        let section = match *name {
+            "feat" => "Features",
+            "fix" => "Bug Fixes",
+            "perf" => "Performance Improvements",
+            "revert" => "Reverts",
+            _ => return entry,
+        };
+        entry.with_changelog_section(section)
+    })
+    .collect()
 }
 
 fn default_type_case() -> String {
     "lowercase".to_string()
 }
 
-fn default_scope_enum() -> Vec<String> {
+fn default_scope_enum() -> Vec<EnumEntry> {
     Vec::new()
 }
 
@@ -138,10 +1005,6 @@ fn default_scope_case() -> String {
     "lowercase".to_string()
 }
 
-fn default_subject_case() -> Vec<String> {
-    vec!["sentence-case".to_string()]
-}
-
 fn default_subject_empty() -> bool {
     false
 }
@@ -174,10 +1037,74 @@ fn default_footer_max_line_length() -> usize {
     100
 }
 
+fn default_gitmoji_types() -> HashMap<String, Vec<String>> {
+    [
+        ("build", &[":package:", ":construction_worker:"][..]),
+        ("chore", &[":wrench:", ":hammer:", ":bookmark:"]),
+        ("ci", &[":construction_worker:", ":green_heart:"]),
+        ("docs", &[":memo:", ":bulb:"]),
+        ("feat", &[":sparkles:", ":tada:"]),
+        ("fix", &[":bug:", ":ambulance:", ":adhesive_bandage:"]),
+        ("perf", &[":zap:"]),
+        ("refactor", &[":recycle:", ":art:"]),
+        ("revert", &[":rewind:"]),
+        ("style", &[":art:", ":lipstick:"]),
+        ("test", &[":white_check_mark:", ":test_tube:"]),
+    ]
+    .iter()
+    .map(|(r#type, emoji)| {
+        let emoji = emoji.iter().map(|e| e.to_string()).collect();
+        (r#type.to_string(), emoji)
+    })
+    .collect()
+}
+
+fn default_entropy_threshold() -> f64 {
+    4.5
+}
+
+fn default_entropy_min_length() -> usize {
+    20
+}
+
+fn default_issue_prefixes() -> Vec<String> {
+    vec!["#".to_string()]
+}
+
+fn default_reference_actions() -> Vec<String> {
+    [
+        "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved",
+        "refs",
+    ]
+    .iter()
+    .map(|action| action.to_string())
+    .collect()
+}
+
+fn default_scope_delimiters() -> Vec<String> {
+    vec![",".to_string()]
+}
+
+fn default_true() -> bool {
+    true
+}
+
 fn default_parser_pattern() -> String {
     r"^(?P<type>\w+)(?:\((?P<scope>[^)]+)\))?(?P<breaking>!)?:\s(?P<subject>.*)$".to_string()
 }
 
+fn default_branch_pattern() -> String {
+    r"^(?P<type>[a-z]+)/(?P<ticket>[A-Z][A-Z0-9]*-\d+|\d+)-(?P<slug>[a-z0-9]+(?:-[a-z0-9]+)*)$"
+        .to_string()
+}
+
+fn default_branch_allow() -> Vec<String> {
+    ["main", "master", "develop", "dependabot/", "renovate/"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
 fn default_parser_correspondence() -> HashMap<String, String> {
     let mut map = HashMap::new();
     map.insert("type".to_string(), "type".to_string());
@@ -187,41 +1114,504 @@ fn default_parser_correspondence() -> HashMap<String, String> {
     map
 }
 
+/// Resolve the relative file paths in a `[rules]` table against `dir`.
+fn resolve_rule_paths(rules: &mut toml::Table, dir: &Path) {
+    if let Some(toml::Value::Table(co_authored_by)) = rules.get_mut("co_authored_by") {
+        if let Some(toml::Value::String(allowlist)) = co_authored_by.get_mut("allowlist") {
+            *allowlist = dir.join(&*allowlist).to_string_lossy().into_owned();
+        }
+    }
+}
+
+/// Top-level keys a nested config may set.
+const NESTED_KEYS: &[&str] = &["rules", "parser"];
+
+/// File names a config is loaded from, in order of preference.
+const CONFIG_FILE_NAMES: &[&str] = &["commitlint.toml", ".commitlint.toml"];
+
 impl Config {
+    /// This config with the `[[when]]` blocks matching `context` applied, in order.
+    pub fn for_context(&self, context: &RunContext) -> anyhow::Result<Config> {
+        let mut config = self.clone();
+        let matching: Vec<&When> = self.when.iter().filter(|w| w.matches(context)).collect();
+        if matching.is_empty() {
+            return Ok(config);
+        }
+
+        let overlays: Vec<&toml::Table> = matching.iter().map(|when| &when.rules).collect();
+        config.rules = self.rules.overlay(&overlays)?;
+        if let Some(ignores) = matching.iter().rev().find_map(|when| when.ignores.as_ref()) {
+            config.ignores = ignores.clone();
+        }
+        Ok(config)
+    }
+
+    /// The config for a commit touching `files` (relative to the repository `root`), with
+    /// the nested configs covering them merged over this one per `nested.span`.
+    pub fn for_paths(&self, root: &Path, files: &[String]) -> anyhow::Result<(Config, Applied)> {
+        let chains = if self.nested.enabled {
+            Self::nested_chains(root, files)
+        } else {
+            Vec::new()
+        };
+
+        match chains.len() {
+            0 => Ok((self.clone(), Applied::Root)),
+            1 => Ok((
+                self.with_nested(root, &chains)?,
+                Applied::Nested(configs_used(&chains)),
+            )),
+            _ if self.nested.span == SpanPolicy::Merge => Ok((
+                self.with_nested(root, &chains)?,
+                Applied::Nested(configs_used(&chains)),
+            )),
+            _ => {
+                let areas = chains.iter().filter_map(|chain| chain.last().cloned());
+                Ok((self.clone(), Applied::Spanning(areas.collect())))
+            }
+        }
+    }
+
+    /// The nested configs covering each of `files`, relative to `root`: one chain per
+    /// nearest config, listing its ancestors first. Sorted by nearest config.
+    fn nested_chains(root: &Path, files: &[String]) -> Vec<Vec<PathBuf>> {
+        let mut chains: Vec<Vec<PathBuf>> = files
+            .iter()
+            .map(|file| {
+                let mut chain: Vec<PathBuf> = Path::new(file)
+                    .ancestors()
+                    .skip(1)
+                    .take_while(|dir| !dir.as_os_str().is_empty())
+                    .filter_map(|dir| {
+                        CONFIG_FILE_NAMES
+                            .iter()
+                            .map(|name| dir.join(name))
+                            .find(|path| root.join(path).is_file())
+                    })
+                    .collect();
+                chain.reverse();
+                chain
+            })
+            .filter(|chain| !chain.is_empty())
+            .collect();
+        chains.sort_by(|a, b| a.last().cmp(&b.last()));
+        chains.dedup();
+        chains
+    }
+
+    /// Merge each chain from its outermost config inwards, so a nearer config replaces
+    /// the values it sets, then combine the chains as `SpanPolicy::Merge` describes.
+    fn with_nested(&self, root: &Path, chains: &[Vec<PathBuf>]) -> anyhow::Result<Config> {
+        let mut nested = toml::Table::new();
+        for chain in chains {
+            let mut area = toml::Table::new();
+            for path in chain {
+                merge_tables(&mut area, &Self::nested_table(root, path)?);
+            }
+            combine_tables(&mut nested, &area);
+        }
+
+        let mut config = toml::Table::try_from(self)?;
+        merge_tables(&mut config, &nested);
+        let config: Config = toml::Value::Table(config).try_into()?;
+        config.check()?;
+        Ok(config)
+    }
+
+    /// The settings of the nested config at `path`, with relative paths resolved against
+    /// its directory. Only `[rules]` and `[parser]` may be set there.
+    fn nested_table(root: &Path, path: &Path) -> anyhow::Result<toml::Table> {
+        let content = std::fs::read_to_string(root.join(path))
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut table: toml::Table = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        if let Some(key) = table
+            .keys()
+            .find(|key| !NESTED_KEYS.contains(&key.as_str()))
+        {
+            anyhow::bail!(
+                "{} sets `{}`, but nested configs may only set [rules] and [parser]",
+                path.display(),
+                key
+            );
+        }
+
+        if let (Some(toml::Value::Table(rules)), Some(dir)) =
+            (table.get_mut("rules"), root.join(path).parent())
+        {
+            resolve_rule_paths(rules, dir);
+            if let Some(toml::Value::Table(overrides)) = rules.get_mut("overrides") {
+                for (_, table) in overrides.iter_mut() {
+                    if let toml::Value::Table(table) = table {
+                        resolve_rule_paths(table, dir);
+                    }
+                }
+            }
+        }
+        Ok(table)
+    }
+
     pub fn from_file(path: &std::path::Path) -> anyhow::Result<Self> {
         if path.exists() {
             let content = std::fs::read_to_string(path)?;
-            let config: Config = toml::from_str(&content)?;
+            let mut config: Config = toml::from_str(&content)?;
+            if let Some(dir) = path.parent() {
+                config.resolve_paths(dir);
+            }
+            config.check()?;
             Ok(config)
         } else {
             Ok(Config::default())
         }
     }
 
+    /// Resolve the relative file paths in this config against `dir`, the directory of the
+    /// file it was loaded from, rather than wherever the command runs.
+    fn resolve_paths(&mut self, dir: &Path) {
+        if let Some(allowlist) = &mut self.rules.co_authored_by.allowlist {
+            *allowlist = dir.join(&*allowlist);
+        }
+        for overrides in self.rules.overrides.values_mut() {
+            resolve_rule_paths(overrides, dir);
+        }
+        for when in &mut self.when {
+            resolve_rule_paths(&mut when.rules, dir);
+        }
+    }
+
+    /// Check the settings that only fail once applied.
+    fn check(&self) -> anyhow::Result<()> {
+        self.rules.check()?;
+        for key in self.rules.overrides.keys() {
+            self.rules
+                .check_override(key)
+                .with_context(|| format!("Invalid [rules.overrides.\"{}\"]", key))?;
+        }
+        regex::Regex::new(&self.branch.pattern).context("Invalid branch.pattern")?;
+        for (i, when) in self.when.iter().enumerate() {
+            self.rules
+                .overlay(&[&when.rules])
+                .and_then(|rules| rules.check())
+                .with_context(|| format!("Invalid [[when]] block #{}", i + 1))?;
+        }
+        Ok(())
+    }
+
     pub fn from_default_locations() -> anyhow::Result<Self> {
+        match Self::find_default_path()? {
+            Some(config_path) => Self::from_file(&config_path),
+            None => Ok(Config::default()),
+        }
+    }
+
+    /// Locate the config file `from_default_locations` would load, if any.
+    pub fn find_default_path() -> anyhow::Result<Option<std::path::PathBuf>> {
         // Try to find config in common locations
         let current_dir = std::env::current_dir()?;
 
-        // Check for commitlint.toml in current directory
-        let config_path = current_dir.join("commitlint.toml");
-        if config_path.exists() {
-            return Self::from_file(&config_path);
-        }
+        let candidates = [
+            // commitlint.toml in current directory
+            current_dir.join("commitlint.toml"),
+            // .commitlint.toml in current directory
+            current_dir.join(".commitlint.toml"),
+            // commitlint.toml in .cargo directory
+            current_dir.join(".cargo").join("commitlint.toml"),
+        ];
 
-        // Check for .commitlint.toml in current directory
-        let config_path = current_dir.join(".commitlint.toml");
-        if config_path.exists() {
-            return Self::from_file(&config_path);
-        }
+        Ok(candidates.into_iter().find(|path| path.exists()))
+    }
+}
 
-        // Check for commitlint.toml in .cargo directory
-        let config_path = current_dir.join(".cargo").join("commitlint.toml");
-        if config_path.exists() {
-            return Self::from_file(&config_path);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_config_parses() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("commitlint.example.toml");
+        let config = Config::from_file(&path).unwrap();
+        assert_eq!(config.rules.header_max_length, 72);
+
+        // Keys placed under the wrong table would be silently ignored
+        fn unknown_keys(raw: &toml::Table, known: &toml::Table, prefix: &str) -> Vec<String> {
+            raw.iter()
+                .flat_map(|(key, value)| match (value, known.get(key)) {
+                    (_, None) => vec![format!("{}{}", prefix, key)],
+                    (toml::Value::Table(raw), Some(toml::Value::Table(known))) => {
+                        unknown_keys(raw, known, &format!("{}{}.", prefix, key))
+                    }
+                    _ => Vec::new(),
+                })
+                .collect()
         }
+        let raw: toml::Table = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let known = toml::Table::try_from(&config).unwrap();
+        assert_eq!(unknown_keys(&raw, &known, ""), Vec::<String>::new());
+    }
 
-        // Default config
-        Ok(Config::default())
+    #[test]
+    fn test_check_rejects_invalid_secret_patterns() {
+        let config: Config = toml::from_str("[rules.no_secrets.patterns]\nbroken = \"(\"").unwrap();
+        assert!(config.check().is_err());
+
+        let config: Config =
+            toml::from_str("[rules.overrides.feat.no_secrets.patterns]\nbroken = \"(\"").unwrap();
+        assert!(config.check().is_err());
+    }
+
+    #[test]
+    fn test_paths_resolve_against_config_dir() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [rules.co_authored_by]
+            allowlist = ".mailmap"
+
+            [rules.overrides.feat.co_authored_by]
+            allowlist = "authors.txt"
+            "#,
+        )
+        .unwrap();
+        config.resolve_paths(Path::new("/repo/.cargo"));
+
+        assert_eq!(
+            config.rules.co_authored_by.allowlist,
+            Some(PathBuf::from("/repo/.cargo/.mailmap"))
+        );
+        let feat = config.rules.for_commit("feat", &[]).unwrap();
+        assert_eq!(
+            feat.co_authored_by.allowlist,
+            Some(PathBuf::from("/repo/.cargo/authors.txt"))
+        );
+    }
+
+    #[test]
+    fn test_rules_for_commit() {
+        let config: Config = toml::from_str(
+            r#"
+            [rules]
+            header_max_length = 100
+
+            [rules.overrides.feat]
+            header_max_length = 60
+            body_empty = true
+
+            [rules.overrides.feat.references]
+            required = true
+
+            [rules.overrides."(deps)"]
+            body_empty = false
+
+            [rules.overrides."feat(api)"]
+            header_max_length = 50
+
+            [rules.overrides.docs]
+            length_unit = "bytes"
+            scope = { case = "kebab-case" }
+            "#,
+        )
+        .unwrap();
+        let rules = &config.rules;
+
+        let feat = rules.for_commit("feat", &[]).unwrap();
+        assert_eq!(feat.header_max_length, 60);
+        assert!(feat.body_empty);
+        assert!(feat.references.required);
+        assert!(!feat.references.validate_syntax);
+
+        let chore = rules.for_commit("chore", &[]).unwrap();
+        assert_eq!(chore.header_max_length, 100);
+        assert!(!chore.body_empty);
+
+        assert!(
+            !rules
+                .for_commit("feat", &["deps".into()])
+                .unwrap()
+                .body_empty
+        );
+        assert_eq!(
+            rules
+                .for_commit("feat", &["api".into()])
+                .unwrap()
+                .header_max_length,
+            50
+        );
+
+        // Each scope of a multi-scope commit is looked up
+        let scopes = ["ui".to_string(), "deps".to_string()];
+        assert!(!rules.for_commit("feat", &scopes).unwrap().body_empty);
+
+        let label = |scope: Option<&str>, rule| {
+            let scopes: Vec<String> = scope.into_iter().map(String::from).collect();
+            rules.override_label("feat", &scopes, rule)
+        };
+        assert_eq!(
+            label(None, "header-max-length").as_deref(),
+            Some("type feat")
+        );
+        assert_eq!(
+            label(None, "references-empty").as_deref(),
+            Some("type feat")
+        );
+        assert_eq!(
+            label(Some("deps"), "body-empty").as_deref(),
+            Some("scope deps")
+        );
+        assert_eq!(
+            label(Some("api"), "header-max-length").as_deref(),
+            Some("feat(api)")
+        );
+        assert_eq!(label(None, "subject-case"), None);
+
+        // Labels follow the keys each rule documents, not the table names
+        let docs = |rule| rules.override_label("docs", &[], rule);
+        assert_eq!(docs("header-max-length").as_deref(), Some("type docs"));
+        assert_eq!(docs("scope-case").as_deref(), Some("type docs"));
+        assert_eq!(docs("scope-enum"), None);
     }
-}
 
+    #[test]
+    fn test_when_blocks() {
+        let config: Config = toml::from_str(
+            r#"
+            ignores = ["^WIP"]
+
+            [[when]]
+            branch = ["main", "release/*"]
+            ignores = []
+
+            [when.rules]
+            breaking = { forbidden = true }
+
+            [when.rules.references]
+            required = true
+
+            [[when]]
+            ci = true
+            hook = "commit-msg"
+            rules = { header_max_length = 50 }
+            "#,
+        )
+        .unwrap();
+
+        let release = RunContext {
+            branch: Some("release/1.2".to_string()),
+            ..RunContext::default()
+        };
+        let resolved = config.for_context(&release).unwrap();
+        assert!(resolved.rules.references.required);
+        assert!(resolved.rules.breaking.forbidden);
+        assert!(resolved.ignores.is_empty());
+        assert_eq!(resolved.rules.header_max_length, 72);
+
+        let nested = RunContext {
+            branch: Some("release/1.2/hotfix".to_string()),
+            ..RunContext::default()
+        };
+        let resolved = config.for_context(&nested).unwrap();
+        assert!(!resolved.rules.references.required);
+        assert_eq!(resolved.ignores, ["^WIP"]);
+
+        let hook = RunContext {
+            ci: true,
+            hook: Some("commit-msg".to_string()),
+            ..RunContext::default()
+        };
+        assert_eq!(
+            config.for_context(&hook).unwrap().rules.header_max_length,
+            50
+        );
+        assert!(glob_matches("feat/**", "feat/a/b"));
+        assert!(!glob_matches("feat/*", "feat/a/b"));
+    }
+
+    #[test]
+    fn test_nested_configs() {
+        let root = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = root.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "frontend/commitlint.toml",
+            "[rules.scope]\nenum = [\"ui\"]\n",
+        );
+        write(
+            "crates/.commitlint.toml",
+            "[rules]\nheader_max_length = 60\n\n[rules.scope]\nenum = [\"core\"]\n",
+        );
+        write(
+            "crates/cli/commitlint.toml",
+            "[rules.scope]\nenum = [\"cli\"]\n",
+        );
+
+        let files =
+            |paths: &[&str]| -> Vec<String> { paths.iter().map(|p| p.to_string()).collect() };
+        let mut config = Config::default();
+        let root = root.path();
+
+        let (resolved, applied) = config.for_paths(root, &files(&["README.md"])).unwrap();
+        assert_eq!(applied, Applied::Root);
+        assert!(resolved.rules.scope.r#enum.is_empty());
+
+        let (resolved, applied) = config
+            .for_paths(root, &files(&["frontend/src/app.ts", "README.md"]))
+            .unwrap();
+        assert_eq!(
+            applied.describe().as_deref(),
+            Some("using frontend/commitlint.toml")
+        );
+        assert_eq!(resolved.rules.scope.r#enum, [EnumEntry::from("ui")]);
+
+        // Ancestor configs apply too, with the nearest one winning
+        let (resolved, applied) = config
+            .for_paths(root, &files(&["crates/cli/main.rs"]))
+            .unwrap();
+        assert_eq!(
+            applied.describe().as_deref(),
+            Some("using crates/.commitlint.toml, crates/cli/commitlint.toml")
+        );
+        assert_eq!(resolved.rules.header_max_length, 60);
+        assert_eq!(resolved.rules.scope.r#enum, [EnumEntry::from("cli")]);
+
+        let spanning = files(&["frontend/app.ts", "crates/lib.rs"]);
+        let (resolved, _) = config.for_paths(root, &spanning).unwrap();
+        assert_eq!(resolved.rules.scope.r#enum, ["core".into(), "ui".into()]);
+
+        config.nested.span = SpanPolicy::Reject;
+        let (resolved, applied) = config.for_paths(root, &spanning).unwrap();
+        assert!(matches!(applied, Applied::Spanning(ref areas) if areas.len() == 2));
+        assert!(resolved.rules.scope.r#enum.is_empty());
+
+        // Only rules and parser settings can differ below the root
+        write("docs/commitlint.toml", "ignores = [\"^docs\"]\n");
+        let error = config
+            .for_paths(root, &files(&["docs/guide.md"]))
+            .unwrap_err();
+        assert!(error.to_string().contains("sets `ignores`"), "{}", error);
+
+        // Relative paths are resolved against the nested config's directory
+        write(
+            "tools/commitlint.toml",
+            "[rules.overrides.feat.co_authored_by]\nallowlist = \".mailmap\"\n",
+        );
+        let (resolved, _) = config.for_paths(root, &files(&["tools/build.rs"])).unwrap();
+        let feat = resolved.rules.for_commit("feat", &[]).unwrap();
+        assert_eq!(
+            feat.co_authored_by.allowlist,
+            Some(root.join("tools").join(".mailmap"))
+        );
+    }
+
+    #[test]
+    fn test_length_units() {
+        let header = "feat: 日本語のサポートを追加 ✨";
+        assert_eq!(LengthUnit::Bytes.measure(header), 43);
+        assert_eq!(LengthUnit::Chars.measure(header), 19);
+        assert_eq!(LengthUnit::Width.measure(header), 31);
+
+        let flag = "🇯🇵";
+        assert_eq!(LengthUnit::Chars.measure(flag), 2);
+        assert_eq!(LengthUnit::Graphemes.measure(flag), 1);
+    }
+}
@@ -0,0 +1,281 @@
+use crate::config::Config;
+use crate::hook::HookInstaller;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use which::which;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn symbol(self) -> &'static str {
+        match self {
+            Status::Pass => "✓",
+            Status::Warn => "⚠",
+            Status::Fail => "✗",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+pub struct Doctor;
+
+impl Doctor {
+    /// Run every check and print the results. Returns `false` if any check failed.
+    pub fn run() -> bool {
+        let checks = Self::checks();
+        print!("{}", Self::render(&checks));
+        checks.iter().all(|check| check.status != Status::Fail)
+    }
+
+    pub fn checks() -> Vec<Check> {
+        let mut checks = Vec::new();
+
+        match HookInstaller::find_git_dir() {
+            Ok(git_dir) => {
+                checks.push(Check::pass("git repository", git_dir.display().to_string()));
+                checks.extend(Self::hook_checks());
+            }
+            Err(e) => {
+                checks.push(Check::fail(
+                    "git repository",
+                    e.to_string(),
+                    "run inside a git repository, or create one with `git init`",
+                ));
+            }
+        }
+
+        checks.push(Self::subcommand_check());
+        checks.push(Self::config_check());
+        checks
+    }
+
+    fn hook_checks() -> Vec<Check> {
+        let mut checks = Vec::new();
+
+        let hooks_dir = match HookInstaller::find_hooks_dir() {
+            Ok(dir) => dir,
+            Err(e) => {
+                checks.push(Check::fail(
+                    "hooks directory",
+                    e.to_string(),
+                    "check that `git rev-parse --git-path hooks` works in this repository",
+                ));
+                return checks;
+            }
+        };
+
+        let detail = match HookInstaller::hooks_path_override() {
+            Some(value) => format!("{} (core.hooksPath = {})", hooks_dir.display(), value),
+            None => hooks_dir.display().to_string(),
+        };
+        checks.push(Check::pass("hooks directory", detail));
+
+        let hook_path = hooks_dir.join("commit-msg");
+        let content = match fs::read_to_string(&hook_path) {
+            Ok(content) => content,
+            Err(_) => {
+                checks.push(Check::fail(
+                    "commit-msg hook",
+                    format!("{} not found", hook_path.display()),
+                    "run `cargo commitlint install`",
+                ));
+                return checks;
+            }
+        };
+
+        if HookInstaller::is_own_hook(&content) {
+            checks.push(Check::pass(
+                "commit-msg hook",
+                hook_path.display().to_string(),
+            ));
+        } else {
+            checks.push(Check::fail(
+                "commit-msg hook",
+                format!("{} was not installed by cargo-commitlint", hook_path.display()),
+                "call `cargo commitlint check` from the existing hook, or replace it with `cargo commitlint install`",
+            ));
+            return checks;
+        }
+
+        checks.push(Self::executable_check(&hook_path));
+
+        match HookInstaller::baked_binary_path(&content) {
+            Some(path) if Self::binary_resolves(&path) => {
+                checks.push(Check::pass("hook binary", path.display().to_string()));
+            }
+            Some(path) => checks.push(Check::fail(
+                "hook binary",
+                format!("{} does not exist", path.display()),
+                "reinstall the hook with `cargo commitlint install`",
+            )),
+            None => checks.push(Check::warn(
+                "hook binary",
+                "no fallback binary path found in hook",
+                "reinstall the hook with `cargo commitlint install`",
+            )),
+        }
+
+        checks
+    }
+
+    #[cfg(unix)]
+    fn executable_check(hook_path: &Path) -> Check {
+        use std::os::unix::fs::PermissionsExt;
+
+        match fs::metadata(hook_path) {
+            Ok(metadata) if metadata.permissions().mode() & 0o111 != 0 => Check::pass(
+                "hook executable",
+                format!("{:o}", metadata.permissions().mode() & 0o777),
+            ),
+            Ok(_) => Check::fail(
+                "hook executable",
+                "hook is not executable, git will skip it",
+                format!("run `chmod +x {}`", hook_path.display()),
+            ),
+            Err(e) => Check::fail(
+                "hook executable",
+                e.to_string(),
+                "reinstall the hook with `cargo commitlint install`",
+            ),
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn executable_check(_hook_path: &Path) -> Check {
+        Check::pass("hook executable", "not applicable on this platform")
+    }
+
+    fn binary_resolves(path: &Path) -> bool {
+        if path.components().count() > 1 {
+            path.exists()
+        } else {
+            which(path).is_ok()
+        }
+    }
+
+    fn subcommand_check() -> Check {
+        let output = Command::new("cargo")
+            .args(["commitlint", "--version"])
+            .output();
+
+        match output {
+            Ok(output) if output.status.success() => Check::pass(
+                "cargo commitlint",
+                String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            ),
+            _ => Check::warn(
+                "cargo commitlint",
+                "`cargo commitlint --version` failed, the hook will use its fallback binary",
+                "install the subcommand with `cargo install cargo-commitlint`",
+            ),
+        }
+    }
+
+    fn config_check() -> Check {
+        let path = match Config::find_default_path() {
+            Ok(Some(path)) => path,
+            Ok(None) => {
+                return Check::pass("config", "no config file found, using built-in defaults")
+            }
+            Err(e) => return Check::fail("config", e.to_string(), "run from the project root"),
+        };
+
+        match Config::from_file(&path) {
+            Ok(_) => Check::pass("config", path.display().to_string()),
+            Err(e) => Check::fail(
+                "config",
+                format!("{}: {}", path.display(), e),
+                "fix the file, using commitlint.example.toml as a reference",
+            ),
+        }
+    }
+
+    pub fn render(checks: &[Check]) -> String {
+        let width = checks
+            .iter()
+            .map(|check| check.name.len())
+            .max()
+            .unwrap_or(0);
+        let mut out = String::new();
+
+        for check in checks {
+            out.push_str(&format!(
+                "  {} {:width$}  {}\n",
+                check.status.symbol(),
+                check.name,
+                check.detail,
+                width = width
+            ));
+            if check.status != Status::Pass {
+                if let Some(ref hint) = check.hint {
+                    out.push_str(&format!("    {:width$}  → {}\n", "", hint, width = width));
+                }
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_shows_hints_for_failures_only() {
+        let checks = vec![
+            Check::pass("config", "commitlint.toml"),
+            Check::fail(
+                "commit-msg hook",
+                "not found",
+                "run `cargo commitlint install`",
+            ),
+        ];
+        let table = Doctor::render(&checks);
+        assert!(table.contains("✓ config"));
+        assert!(table.contains("✗ commit-msg hook"));
+        assert_eq!(table.matches('→').count(), 1);
+    }
+}
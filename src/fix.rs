@@ -0,0 +1,157 @@
+use crate::commit::{self, CommitMessage};
+use crate::config::Config;
+use crate::git::Author;
+use crate::gitmoji;
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// A message rewritten by `apply`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixed {
+    pub message: String,
+    /// A rewrite worth mentioning in the report, e.g. resolved aliases
+    pub note: Option<String>,
+}
+
+/// Apply the configured automatic fixes to a message being committed.
+///
+/// Returns the rewritten message, or `None` if nothing needed fixing.
+pub fn apply(config: &Config, message: &str) -> Result<Option<Fixed>> {
+    let mut fixed = message.to_string();
+    let msg = CommitMessage::from_str(message);
+
+    let sign_off = &config.rules.signed_off_by;
+    if sign_off.required && sign_off.autofix && msg.sign_offs().is_empty() {
+        let author = Author::from_git_config()?;
+        fixed = commit::append_trailer(&fixed, &format!("Signed-off-by: {}", author));
+    }
+
+    if let Some(format) = config.rules.gitmoji.format {
+        if let (Some(emoji), rest) = gitmoji::split_prefix(&msg.header) {
+            if let Some(converted) = gitmoji::convert(emoji, format) {
+                let header = format!("{} {}", converted, rest);
+                fixed = fixed.replacen(&msg.header, &header, 1);
+            }
+        }
+    }
+
+    let mut note = None;
+    let header = fixed.lines().next().unwrap_or_default().to_string();
+    if let Some(resolved) = resolve_aliases(config, &header) {
+        fixed = fixed.replacen(&header, &resolved, 1);
+        note = Some(format!("resolved aliases in '{}'", header));
+    }
+
+    if fixed == message {
+        Ok(None)
+    } else {
+        Ok(Some(Fixed {
+            message: fixed,
+            note,
+        }))
+    }
+}
+
+/// `header` with type and scope aliases replaced by the names they stand for, if any.
+fn resolve_aliases(config: &Config, header: &str) -> Option<String> {
+    let rules = &config.rules;
+    if rules.r#type.aliases.is_empty() && rules.scope.aliases.is_empty() {
+        return None;
+    }
+
+    let (_, rest) = gitmoji::split_prefix(header);
+    let prefix = &header[..header.len() - rest.len()];
+    let caps = Regex::new(&config.parser.pattern).ok()?.captures(rest)?;
+
+    // Rewrite from the right so the type's range stays valid
+    let mut resolved = rest.to_string();
+    if let Some(scope) = caps.name("scope") {
+        let scopes = resolve_scopes(
+            scope.as_str(),
+            &config.parser.scope_delimiters,
+            &rules.scope.aliases,
+        );
+        resolved.replace_range(scope.range(), &scopes);
+    }
+    if let Some(commit_type) = caps.name("type") {
+        if let Some(canonical) = rules.r#type.aliases.get(commit_type.as_str()) {
+            resolved.replace_range(commit_type.range(), canonical);
+        }
+    }
+
+    let resolved = format!("{}{}", prefix, resolved);
+    (resolved != header).then_some(resolved)
+}
+
+/// `scope` with each aliased scope replaced, keeping the delimiters and spacing.
+fn resolve_scopes(scope: &str, delimiters: &[String], aliases: &HashMap<String, String>) -> String {
+    let mut resolved = String::new();
+    let mut segment = String::new();
+    let flush = |segment: &mut String, resolved: &mut String| {
+        let name = segment.trim();
+        match aliases.get(name) {
+            Some(canonical) => resolved.push_str(&segment.replacen(name, canonical, 1)),
+            None => resolved.push_str(segment),
+        }
+        segment.clear();
+    };
+
+    let mut rest = scope;
+    while let Some(c) = rest.chars().next() {
+        match delimiters
+            .iter()
+            .find(|d| !d.is_empty() && rest.starts_with(d.as_str()))
+        {
+            Some(delimiter) => {
+                flush(&mut segment, &mut resolved);
+                resolved.push_str(delimiter);
+                rest = &rest[delimiter.len()..];
+            }
+            None => {
+                segment.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    flush(&mut segment, &mut resolved);
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_aliases() {
+        let mut config = Config::default();
+        let aliases = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(a, b)| (a.to_string(), b.to_string()))
+                .collect()
+        };
+        config.rules.r#type.aliases = aliases(&[("feature", "feat"), ("bugfix", "fix")]);
+        config.rules.scope.aliases = aliases(&[("frontend", "ui")]);
+
+        assert_eq!(
+            resolve_aliases(&config, "feature(api, frontend): add export").as_deref(),
+            Some("feat(api, ui): add export")
+        );
+        assert_eq!(
+            resolve_aliases(&config, ":bug: bugfix: handle empty input").as_deref(),
+            Some(":bug: fix: handle empty input")
+        );
+        assert_eq!(resolve_aliases(&config, "feat: add export"), None);
+
+        let fixed = apply(&config, "bugfix: handle empty input\n")
+            .unwrap()
+            .unwrap();
+        assert_eq!(fixed.message, "fix: handle empty input\n");
+        assert_eq!(
+            fixed.note.as_deref(),
+            Some("resolved aliases in 'bugfix: handle empty input'")
+        );
+        assert_eq!(apply(&config, "fix: handle empty input\n").unwrap(), None);
+    }
+}
@@ -0,0 +1,210 @@
+use anyhow::{Context, Result};
+use std::process::Command;
+
+/// A commit's author identity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Author {
+    pub name: String,
+    pub email: String,
+}
+
+impl Author {
+    /// The identity git will record for new commits (`user.name` / `user.email`).
+    pub fn from_git_config() -> Result<Self> {
+        Ok(Self {
+            name: config_value("user.name")?,
+            email: config_value("user.email")?,
+        })
+    }
+}
+
+impl std::fmt::Display for Author {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CommitInfo {
+    pub sha: String,
+    pub author: Author,
+    pub message: String,
+}
+
+impl CommitInfo {
+    pub fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(7)]
+    }
+}
+
+/// Commits reachable from `to` but not from `from`, oldest first.
+pub fn commits_in_range(from: &str, to: &str) -> Result<Vec<CommitInfo>> {
+    let range = format!("{}..{}", from, to);
+    log(&["--reverse", &range])
+}
+
+/// Commits carried by the bundle at `path`, oldest first.
+///
+/// The bundle is unpacked into a temporary repository, leaving the current one untouched.
+/// An incremental bundle's prerequisite commits are read from the current repository.
+pub fn commits_in_bundle(path: &std::path::Path) -> Result<Vec<CommitInfo>> {
+    let content =
+        std::fs::read(path).with_context(|| format!("Failed to read bundle {}", path.display()))?;
+    if !content.starts_with(b"# v2 git bundle") && !content.starts_with(b"# v3 git bundle") {
+        anyhow::bail!("{} is not a git bundle", path.display());
+    }
+    // The header ends at the first blank line; the pack data follows
+    let end = content
+        .windows(2)
+        .position(|pair| pair == b"\n\n")
+        .unwrap_or(content.len());
+    let header = String::from_utf8_lossy(&content[..end]);
+
+    // Heads are "<sha> <ref>" lines, prerequisites "-<sha> <subject>"
+    let mut revs: Vec<String> = Vec::new();
+    for line in header.lines().skip(1).filter(|line| !line.starts_with('@')) {
+        let sha = line.split_whitespace().next().unwrap_or_default();
+        match sha.strip_prefix('-') {
+            Some(prerequisite) => revs.push(format!("^{}", prerequisite)),
+            None => revs.push(sha.to_string()),
+        }
+    }
+
+    let scratch = tempfile::tempdir().context("Failed to create a temporary repository")?;
+    let git_dir = format!("--git-dir={}", scratch.path().display());
+    run(&[&git_dir, "init", "--quiet", "--bare"])?;
+    if let Ok(objects) = run(&["rev-parse", "--git-path", "objects"]) {
+        let objects = std::fs::canonicalize(objects.trim())?;
+        let alternates = scratch
+            .path()
+            .join("objects")
+            .join("info")
+            .join("alternates");
+        std::fs::write(alternates, objects.to_string_lossy().as_bytes())?;
+    }
+    run(&[&git_dir, "bundle", "unbundle", &path.to_string_lossy()])?;
+
+    let mut args = vec!["--reverse"];
+    args.extend(revs.iter().map(String::as_str));
+    log_in(&[&git_dir], &args)
+}
+
+/// The last `count` commits reachable from HEAD, newest first.
+pub fn recent_commits(count: usize) -> Result<Vec<CommitInfo>> {
+    let count = format!("--max-count={}", count);
+    log(&[&count, "HEAD"])
+}
+
+fn log(args: &[&str]) -> Result<Vec<CommitInfo>> {
+    log_in(&[], args)
+}
+
+/// `git log` with `options` such as `--git-dir` given to git before the command.
+fn log_in(options: &[&str], args: &[&str]) -> Result<Vec<CommitInfo>> {
+    let mut log_args = options.to_vec();
+    log_args.extend(["log", "--format=%H%x1f%an%x1f%ae%x1f%B%x1e"]);
+    log_args.extend_from_slice(args);
+    let output = run(&log_args)?;
+
+    Ok(output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(4, '\x1f');
+            let sha = fields.next()?.to_string();
+            let name = fields.next()?.to_string();
+            let email = fields.next()?.to_string();
+            let message = fields.next()?.trim_end().to_string();
+            Some(CommitInfo {
+                sha,
+                author: Author { name, email },
+                message,
+            })
+        })
+        .collect())
+}
+
+/// The name of the checked-out branch, or with a detached HEAD in CI, the branch being
+/// built.
+pub fn current_branch() -> Result<String> {
+    match run(&["symbolic-ref", "--short", "HEAD"]) {
+        Ok(name) => Ok(name.trim().to_string()),
+        Err(e) => ["GITHUB_HEAD_REF", "GITHUB_REF_NAME", "CI_COMMIT_REF_NAME"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|name| !name.is_empty())
+            .ok_or(e)
+            .context("HEAD is not on a branch"),
+    }
+}
+
+/// The branch `rev` refers to, e.g. `main` for `origin/main`, if it names one.
+pub fn branch_name(rev: &str) -> Option<String> {
+    let name = run(&["rev-parse", "--symbolic-full-name", rev]).ok()?;
+    let name = name.trim();
+    match name.strip_prefix("refs/heads/") {
+        Some(branch) => Some(branch.to_string()),
+        None => name
+            .strip_prefix("refs/remotes/")?
+            .split_once('/')
+            .map(|(_, branch)| branch.to_string()),
+    }
+}
+
+/// The root of the working tree.
+pub fn toplevel() -> Result<std::path::PathBuf> {
+    Ok(run(&["rev-parse", "--show-toplevel"])?.trim().into())
+}
+
+/// Files staged for the next commit, relative to the root of the working tree.
+pub fn staged_files() -> Result<Vec<String>> {
+    Ok(lines(&run(&["diff", "--cached", "--name-only"])?))
+}
+
+/// Files changed by commit `sha`, relative to the root of the working tree.
+pub fn changed_files(sha: &str) -> Result<Vec<String>> {
+    let output = run(&[
+        "diff-tree",
+        "--no-commit-id",
+        "--name-only",
+        "-r",
+        "--root",
+        sha,
+    ])?;
+    Ok(lines(&output))
+}
+
+fn lines(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The comment character set by `core.commentChar`, `auto`, or `#` by default.
+pub fn comment_char() -> String {
+    config_value("core.commentChar").unwrap_or_else(|_| "#".to_string())
+}
+
+fn config_value(key: &str) -> Result<String> {
+    let value =
+        run(&["config", "--get", key]).with_context(|| format!("git config {} is not set", key))?;
+    Ok(value.trim().to_string())
+}
+
+fn run(args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
@@ -0,0 +1,192 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// The form an emoji prefix is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmojiFormat {
+    /// The emoji itself, e.g. `✨`
+    Unicode,
+    /// A gitmoji shortcode, e.g. `:sparkles:`
+    Shortcode,
+}
+
+/// The gitmoji list (https://gitmoji.dev), as (emoji, shortcode) pairs.
+const GITMOJIS: &[(&str, &str)] = &[
+    ("🎨", ":art:"),
+    ("⚡️", ":zap:"),
+    ("🔥", ":fire:"),
+    ("🐛", ":bug:"),
+    ("🚑️", ":ambulance:"),
+    ("✨", ":sparkles:"),
+    ("📝", ":memo:"),
+    ("🚀", ":rocket:"),
+    ("💄", ":lipstick:"),
+    ("🎉", ":tada:"),
+    ("✅", ":white_check_mark:"),
+    ("🔒️", ":lock:"),
+    ("🔐", ":closed_lock_with_key:"),
+    ("🔖", ":bookmark:"),
+    ("🚨", ":rotating_light:"),
+    ("🚧", ":construction:"),
+    ("💚", ":green_heart:"),
+    ("⬇️", ":arrow_down:"),
+    ("⬆️", ":arrow_up:"),
+    ("📌", ":pushpin:"),
+    ("👷", ":construction_worker:"),
+    ("📈", ":chart_with_upwards_trend:"),
+    ("♻️", ":recycle:"),
+    ("➕", ":heavy_plus_sign:"),
+    ("➖", ":heavy_minus_sign:"),
+    ("🔧", ":wrench:"),
+    ("🔨", ":hammer:"),
+    ("🌐", ":globe_with_meridians:"),
+    ("✏️", ":pencil2:"),
+    ("💩", ":poop:"),
+    ("⏪️", ":rewind:"),
+    ("🔀", ":twisted_rightwards_arrows:"),
+    ("📦️", ":package:"),
+    ("👽️", ":alien:"),
+    ("🚚", ":truck:"),
+    ("📄", ":page_facing_up:"),
+    ("💥", ":boom:"),
+    ("🍱", ":bento:"),
+    ("♿️", ":wheelchair:"),
+    ("💡", ":bulb:"),
+    ("🍻", ":beers:"),
+    ("💬", ":speech_balloon:"),
+    ("🗃️", ":card_file_box:"),
+    ("🔊", ":loud_sound:"),
+    ("🔇", ":mute:"),
+    ("👥", ":busts_in_silhouette:"),
+    ("🚸", ":children_crossing:"),
+    ("🏗️", ":building_construction:"),
+    ("📱", ":iphone:"),
+    ("🤡", ":clown_face:"),
+    ("🥚", ":egg:"),
+    ("🙈", ":see_no_evil:"),
+    ("📸", ":camera_flash:"),
+    ("⚗️", ":alembic:"),
+    ("🔍️", ":mag:"),
+    ("🏷️", ":label:"),
+    ("🌱", ":seedling:"),
+    ("🚩", ":triangular_flag_on_post:"),
+    ("🥅", ":goal_net:"),
+    ("💫", ":dizzy:"),
+    ("🗑️", ":wastebasket:"),
+    ("🛂", ":passport_control:"),
+    ("🩹", ":adhesive_bandage:"),
+    ("🧐", ":monocle_face:"),
+    ("⚰️", ":coffin:"),
+    ("🧪", ":test_tube:"),
+    ("👔", ":necktie:"),
+    ("🩺", ":stethoscope:"),
+    ("🧱", ":bricks:"),
+    ("🧑‍💻", ":technologist:"),
+    ("💸", ":money_with_wings:"),
+    ("🧵", ":thread:"),
+    ("🦺", ":safety_vest:"),
+];
+
+/// Split a leading emoji or `:shortcode:` off `header`.
+///
+/// Returns the emoji as written and the rest of the header with the separating
+/// whitespace removed.
+pub fn split_prefix(header: &str) -> (Option<&str>, &str) {
+    let shortcode = Regex::new(r"^:[a-z0-9_+-]+:").unwrap();
+    let end = match shortcode.find(header) {
+        Some(m) => m.end(),
+        None => header
+            .char_indices()
+            .find(|&(_, c)| !is_emoji_char(c))
+            .map(|(i, _)| i)
+            .unwrap_or(header.len()),
+    };
+
+    if end == 0 {
+        (None, header)
+    } else {
+        (Some(&header[..end]), header[end..].trim_start())
+    }
+}
+
+/// Whether `c` can be part of an emoji sequence.
+fn is_emoji_char(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x00A9 | 0x00AE | 0x203C | 0x2049 | 0x2122 | 0x2139
+            | 0x2190..=0x21FF
+            | 0x2300..=0x27BF
+            | 0x2934..=0x2935
+            | 0x2B00..=0x2BFF
+            | 0x3030 | 0x303D | 0x3297 | 0x3299
+            | 0x1F000..=0x1FAFF
+            // Zero width joiner, variation selector, keycap and tag characters
+            | 0x200D | 0xFE0F | 0x20E3
+            | 0xE0020..=0xE007F
+    )
+}
+
+/// The emoji without variation selectors, so `⚡` and `⚡️` compare equal.
+fn strip_variation(emoji: &str) -> String {
+    emoji.chars().filter(|&c| c != '\u{FE0F}').collect()
+}
+
+fn lookup(emoji: &str) -> Option<&'static (&'static str, &'static str)> {
+    let emoji = strip_variation(emoji);
+    GITMOJIS
+        .iter()
+        .find(|(unicode, shortcode)| *shortcode == emoji || strip_variation(unicode) == emoji)
+}
+
+/// The form `emoji` is written in.
+pub fn format_of(emoji: &str) -> EmojiFormat {
+    if emoji.starts_with(':') {
+        EmojiFormat::Shortcode
+    } else {
+        EmojiFormat::Unicode
+    }
+}
+
+/// Rewrite `emoji` in `format`, if it is a known gitmoji.
+pub fn convert(emoji: &str, format: EmojiFormat) -> Option<&'static str> {
+    lookup(emoji).map(|(unicode, shortcode)| match format {
+        EmojiFormat::Unicode => *unicode,
+        EmojiFormat::Shortcode => *shortcode,
+    })
+}
+
+/// Whether two emoji denote the same gitmoji, whatever form each is written in.
+pub fn same(a: &str, b: &str) -> bool {
+    match (lookup(a), lookup(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => strip_variation(a) == strip_variation(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_prefix() {
+        assert_eq!(
+            split_prefix("✨ feat(api): add x"),
+            (Some("✨"), "feat(api): add x")
+        );
+        assert_eq!(
+            split_prefix(":sparkles: feat: add x"),
+            (Some(":sparkles:"), "feat: add x")
+        );
+        assert_eq!(split_prefix("⚡️perf: faster"), (Some("⚡️"), "perf: faster"));
+        assert_eq!(split_prefix("feat: add ✨"), (None, "feat: add ✨"));
+    }
+
+    #[test]
+    fn test_normalisation() {
+        assert_eq!(convert(":zap:", EmojiFormat::Unicode), Some("⚡️"));
+        assert_eq!(convert("⚡", EmojiFormat::Shortcode), Some(":zap:"));
+        assert!(same("🐛", ":bug:"));
+        assert!(!same("🐛", ":sparkles:"));
+    }
+}
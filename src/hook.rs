@@ -4,17 +4,38 @@ use std::path::Path;
 use std::process::Command;
 use which::which;
 
+/// Line written into every generated hook, so only hooks `install` wrote are ever removed.
+const HOOK_MARKER: &str = "# cargo-commitlint managed hook";
+
+/// First comment of commit-msg hooks written before `HOOK_MARKER` existed.
+const LEGACY_HOOK_HEADER: &str = "# Git commit-msg hook installed by cargo commitlint";
+
 pub struct HookInstaller;
 
 impl HookInstaller {
-    pub fn install() -> Result<()> {
-        let git_dir = Self::find_git_dir()?;
-        let hooks_dir = git_dir.join("hooks");
+    /// Write the commit-msg hook, and the pre-push hook when `pre_push` is set.
+    ///
+    /// An existing pre-push hook not written by `install` is only replaced with `force`.
+    pub fn install(pre_push: bool, force: bool) -> Result<()> {
+        let hooks_dir = Self::find_hooks_dir()?;
+
+        let pre_push_path = hooks_dir.join("pre-push");
+        if pre_push && !force {
+            if let Ok(content) = fs::read_to_string(&pre_push_path) {
+                if !Self::has_marker(&content) {
+                    anyhow::bail!(
+                        "{} was not written by cargo-commitlint; call `cargo commitlint branch` \
+                         from it, or pass --force to replace it",
+                        pre_push_path.display()
+                    );
+                }
+            }
+        }
 
         // Create hooks directory if it doesn't exist
         if !hooks_dir.exists() {
             fs::create_dir_all(&hooks_dir)
-                .context("Failed to create .git/hooks directory")?;
+                .with_context(|| format!("Failed to create {}", hooks_dir.display()))?;
         }
 
         // Find cargo-commitlint binary
@@ -22,34 +43,37 @@ impl HookInstaller {
 
         // Create commit-msg hook
         let hook_path = hooks_dir.join("commit-msg");
-        let hook_content = Self::generate_hook_script(&binary_path);
-
-        fs::write(&hook_path, hook_content)
+        Self::write_hook(&hook_path, &Self::generate_hook_script(&binary_path))
             .context("Failed to write commit-msg hook")?;
 
-        // Make hook executable
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = fs::metadata(&hook_path)?.permissions();
-            perms.set_mode(0o755);
-            fs::set_permissions(&hook_path, perms)?;
-        }
-
         println!("✓ Git hook installed successfully at {}", hook_path.display());
         println!("  Commit messages will now be validated using cargo-commitlint");
 
+        if pre_push {
+            Self::write_hook(
+                &pre_push_path,
+                &Self::generate_pre_push_script(&binary_path),
+            )
+            .context("Failed to write pre-push hook")?;
+
+            println!(
+                "✓ Git hook installed successfully at {}",
+                pre_push_path.display()
+            );
+            println!("  Pushed branch names will now be validated using cargo-commitlint");
+        }
+
         Ok(())
     }
 
     pub fn uninstall() -> Result<()> {
-        let git_dir = Self::find_git_dir()?;
-        let hook_path = git_dir.join("hooks").join("commit-msg");
+        let hooks_dir = Self::find_hooks_dir()?;
+        let hook_path = hooks_dir.join("commit-msg");
 
         if hook_path.exists() {
             // Check if it's our hook
             let content = fs::read_to_string(&hook_path)?;
-            if content.contains("cargo-commitlint") {
+            if Self::is_own_hook(&content) {
                 fs::remove_file(&hook_path)?;
                 println!("✓ Git hook uninstalled successfully");
             } else {
@@ -60,10 +84,32 @@ impl HookInstaller {
             println!("ℹ No commit-msg hook found");
         }
 
+        // The pre-push hook is only installed when `branch.pre_push` is set
+        let pre_push_path = hooks_dir.join("pre-push");
+        if pre_push_path.exists() && Self::has_marker(&fs::read_to_string(&pre_push_path)?) {
+            fs::remove_file(&pre_push_path)?;
+            println!("✓ pre-push hook uninstalled successfully");
+        }
+
         Ok(())
     }
 
-    fn find_git_dir() -> Result<std::path::PathBuf> {
+    fn write_hook(hook_path: &Path, content: &str) -> Result<()> {
+        fs::write(hook_path, content)?;
+
+        // Make hook executable
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = fs::metadata(hook_path)?.permissions();
+            perms.set_mode(0o755);
+            fs::set_permissions(hook_path, perms)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn find_git_dir() -> Result<std::path::PathBuf> {
         let current_dir = std::env::current_dir()?;
         let mut dir = current_dir.as_path();
 
@@ -80,6 +126,76 @@ impl HookInstaller {
         }
     }
 
+    /// Resolve the directory git actually runs hooks from, honouring `core.hooksPath`.
+    pub fn find_hooks_dir() -> Result<std::path::PathBuf> {
+        let git_dir = Self::find_git_dir()?;
+
+        // `--git-path hooks` already accounts for core.hooksPath and worktrees
+        let output = Command::new("git")
+            .args(["rev-parse", "--git-path", "hooks"])
+            .output();
+
+        if let Ok(output) = output {
+            if output.status.success() {
+                let hooks_dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                if !hooks_dir.is_empty() {
+                    return Ok(std::env::current_dir()?.join(hooks_dir));
+                }
+            }
+        }
+
+        Ok(git_dir.join("hooks"))
+    }
+
+    /// The configured `core.hooksPath`, if any.
+    pub fn hooks_path_override() -> Option<String> {
+        let output = Command::new("git")
+            .args(["config", "--get", "core.hooksPath"])
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if value.is_empty() {
+            None
+        } else {
+            Some(value)
+        }
+    }
+
+    /// Whether the hook script at `content` was written by `install`.
+    ///
+    /// Only the marker line counts: a hook merely calling `cargo commitlint` is not ours.
+    pub fn is_own_hook(content: &str) -> bool {
+        Self::has_marker(content) || content.lines().any(|line| line == LEGACY_HOOK_HEADER)
+    }
+
+    /// Whether `content` carries `HOOK_MARKER`, as every hook written since it exists does.
+    fn has_marker(content: &str) -> bool {
+        content.lines().any(|line| line == HOOK_MARKER)
+    }
+
+    /// Extract the fallback binary path baked into a hook by `generate_hook_script`.
+    pub fn baked_binary_path(content: &str) -> Option<std::path::PathBuf> {
+        content.lines().find_map(|line| {
+            let line = line.trim();
+            // Hooks from older versions piped the message file through stdin
+            let command = line
+                .strip_prefix("cat \"$COMMIT_MSG_FILE\" | ")
+                .unwrap_or(line);
+            if command.starts_with("cargo commitlint") {
+                return None;
+            }
+            let path = command
+                .strip_suffix(" check --edit \"$COMMIT_MSG_FILE\"")
+                .or_else(|| command.strip_suffix(" check"))?;
+            Some(std::path::PathBuf::from(path))
+        })
+    }
+
     fn find_binary() -> Result<std::path::PathBuf> {
         // Try to find cargo-commitlint in PATH
         if let Ok(path) = which("cargo-commitlint") {
@@ -88,7 +204,7 @@ impl HookInstaller {
 
         // Try to find via cargo
         let output = Command::new("cargo")
-            .args(&["locate-project", "--workspace", "--message-format", "plain"])
+            .args(["locate-project", "--workspace", "--message-format", "plain"])
             .output()?;
 
         if output.status.success() {
@@ -120,6 +236,7 @@ impl HookInstaller {
 
         format!(
             r#"#!/bin/sh
+{marker}
 # Git commit-msg hook installed by cargo commitlint
 # This hook validates commit messages according to Conventional Commits specification
 
@@ -128,14 +245,45 @@ COMMIT_MSG_FILE="$1"
 # Try to use cargo commitlint subcommand first (if installed)
 if command -v cargo >/dev/null 2>&1 && cargo commitlint --version >/dev/null 2>&1; then
     # Use cargo commitlint subcommand
-    cat "$COMMIT_MSG_FILE" | cargo commitlint check
+    cargo commitlint check --edit "$COMMIT_MSG_FILE"
     exit $?
 else
     # Fall back to direct binary path
-    cat "$COMMIT_MSG_FILE" | {bin_path} check
+    {bin_path} check --edit "$COMMIT_MSG_FILE"
     exit $?
 fi
 "#,
+            marker = HOOK_MARKER,
+            bin_path = path_str
+        )
+    }
+
+    fn generate_pre_push_script(binary_path: &Path) -> String {
+        let path_str = binary_path.to_string_lossy();
+
+        format!(
+            r#"#!/bin/sh
+{marker}
+# Git pre-push hook installed by cargo commitlint
+# This hook validates the names of pushed branches
+
+if command -v cargo >/dev/null 2>&1 && cargo commitlint --version >/dev/null 2>&1; then
+    commitlint() {{ cargo commitlint "$@"; }}
+else
+    commitlint() {{ "{bin_path}" "$@"; }}
+fi
+
+# git passes "<local ref> <local sha> <remote ref> <remote sha>" per pushed ref; the
+# remote ref is the branch being created or updated, whatever the local name
+while read -r local_ref local_sha remote_ref remote_sha; do
+    [ "$local_ref" = "(delete)" ] && continue
+    case "$remote_ref" in
+        refs/heads/*) commitlint branch "${{remote_ref#refs/heads/}}" || exit 1 ;;
+    esac
+done
+exit 0
+"#,
+            marker = HOOK_MARKER,
             bin_path = path_str
         )
     }
@@ -151,6 +299,36 @@ mod tests {
         let script = HookInstaller::generate_hook_script(path);
         assert!(script.contains("cargo-commitlint"));
         assert!(script.contains("/usr/local/bin/cargo-commitlint"));
+
+        let script = HookInstaller::generate_pre_push_script(path);
+        assert!(HookInstaller::is_own_hook(&script));
+        assert!(HookInstaller::has_marker(&script));
+        assert!(script.contains("\"/usr/local/bin/cargo-commitlint\" \"$@\""));
+        assert!(script.contains("branch \"${remote_ref#refs/heads/}\""));
+        assert_eq!(HookInstaller::baked_binary_path(&script), None);
     }
-}
 
+    #[test]
+    fn test_baked_binary_path_roundtrip() {
+        let path = Path::new("/usr/local/bin/cargo-commitlint");
+        let script = HookInstaller::generate_hook_script(path);
+        assert!(HookInstaller::is_own_hook(&script));
+        assert_eq!(
+            HookInstaller::baked_binary_path(&script),
+            Some(path.to_path_buf())
+        );
+        assert_eq!(
+            HookInstaller::baked_binary_path("#!/bin/sh\nexit 0\n"),
+            None
+        );
+        assert!(!HookInstaller::is_own_hook(
+            "#!/bin/sh\ncargo commitlint check --edit \"$1\"\n"
+        ));
+
+        let legacy = "cat \"$COMMIT_MSG_FILE\" | /opt/cargo-commitlint check\n";
+        assert_eq!(
+            HookInstaller::baked_binary_path(legacy),
+            Some(std::path::PathBuf::from("/opt/cargo-commitlint"))
+        );
+    }
+}
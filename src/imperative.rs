@@ -0,0 +1,230 @@
+use std::collections::HashSet;
+
+/// Verbs commit subjects commonly start with, in the imperative (base) form.
+#[rustfmt::skip]
+const VERBS: &[&str] = &[
+    "accept", "access", "adapt", "add", "adjust", "align", "allow", "amend", "annotate",
+    "append", "apply", "archive", "assert", "assign", "avoid", "backport", "ban", "bind",
+    "block", "bootstrap", "break", "bring", "build", "bump", "cache", "calculate", "call",
+    "cancel", "capture", "catch", "center", "change", "check", "clarify", "clean", "cleanup",
+    "clear", "clone", "close", "collapse", "collect", "combine", "comment", "commit", "compile",
+    "complete", "compress", "compute", "configure", "connect", "consolidate", "construct",
+    "contain", "convert", "copy", "correct", "count", "cover", "crash", "create", "cut", "deal",
+    "debug", "declare", "decode", "decouple", "decrease", "default", "defer", "define", "delay",
+    "delegate", "delete", "deny", "deprecate", "describe", "detect", "determine", "disable",
+    "discard", "display", "document", "do", "downgrade", "drop", "dump", "duplicate", "edit",
+    "embed", "emit", "enable", "encode", "enforce", "ensure", "escape", "evaluate", "exclude",
+    "execute", "expand", "expect", "explain", "export", "expose", "extend", "extract", "fail",
+    "fetch", "fill", "filter", "finalize", "find", "finish", "fix", "flatten", "flush", "fold",
+    "force", "fork", "format", "forward", "free", "freeze", "generate", "get", "give", "guard",
+    "handle", "hide", "highlight", "hold", "hook", "ignore", "implement", "import", "improve",
+    "include", "increase", "indent", "index", "infer", "initialize", "inject", "inline",
+    "insert", "install", "integrate", "introduce", "invalidate", "invert", "invoke", "isolate",
+    "keep", "kill", "launch", "lint", "list", "load", "localize", "lock", "log", "lower",
+    "maintain", "make", "manage", "map", "mark", "match", "merge", "migrate", "minimize",
+    "mock", "modify", "move", "normalize", "note", "notify", "omit", "open", "optimize",
+    "order", "output", "overhaul", "override", "parse", "pass", "patch", "pin", "place",
+    "polish", "populate", "port", "prefer", "prepare", "prevent", "print", "process",
+    "prohibit", "propagate", "protect", "provide", "prune", "publish", "pull", "push", "put",
+    "raise", "read", "rebase", "rebuild", "recover", "redirect", "reduce", "refactor", "refine",
+    "reformat", "refresh", "register", "reject", "release", "reload", "remove", "rename",
+    "render", "reorder", "reorganize", "repair", "replace", "report", "request", "require",
+    "reset", "resize", "resolve", "restore", "restrict", "restructure", "retry", "return",
+    "reuse", "revert", "review", "revise", "rewrite", "rework", "run", "save", "scan",
+    "schedule", "search", "secure", "select", "send", "separate", "serialize", "serve", "set",
+    "setup", "share", "show", "shrink", "simplify", "skip", "sort", "specify", "speed", "split",
+    "squash", "stabilize", "start", "stop", "store", "strip", "style", "submit", "support",
+    "suppress", "swap", "switch", "sync", "tag", "take", "tear", "test", "throw", "tidy",
+    "toggle", "track", "transform", "translate", "trigger", "trim", "truncate", "tune", "tweak",
+    "undo", "unify", "uninstall", "unlock", "unpin", "unwrap", "update", "upgrade", "upload",
+    "use", "validate", "verify", "wait", "warn", "watch", "wrap", "write",
+];
+
+/// Irregular past tense and participle forms.
+const IRREGULAR: &[(&str, &str)] = &[
+    ("began", "begin"),
+    ("begun", "begin"),
+    ("bound", "bind"),
+    ("broke", "break"),
+    ("broken", "break"),
+    ("brought", "bring"),
+    ("built", "build"),
+    ("caught", "catch"),
+    ("chose", "choose"),
+    ("did", "do"),
+    ("does", "do"),
+    ("done", "do"),
+    ("drew", "draw"),
+    ("dealt", "deal"),
+    ("found", "find"),
+    ("froze", "freeze"),
+    ("frozen", "freeze"),
+    ("gave", "give"),
+    ("given", "give"),
+    ("got", "get"),
+    ("went", "go"),
+    ("hid", "hide"),
+    ("hidden", "hide"),
+    ("held", "hold"),
+    ("kept", "keep"),
+    ("made", "make"),
+    ("overrode", "override"),
+    ("overridden", "override"),
+    ("ran", "run"),
+    ("rebuilt", "rebuild"),
+    ("rewrote", "rewrite"),
+    ("rewritten", "rewrite"),
+    ("sent", "send"),
+    ("shown", "show"),
+    ("shrank", "shrink"),
+    ("shrunk", "shrink"),
+    ("took", "take"),
+    ("taken", "take"),
+    ("threw", "throw"),
+    ("thrown", "throw"),
+    ("tore", "tear"),
+    ("torn", "tear"),
+    ("undid", "undo"),
+    ("wrote", "write"),
+    ("written", "write"),
+];
+
+/// Suffix rewrites from an inflected form back to candidate base forms.
+const SUFFIXES: &[(&str, &[&str])] = &[
+    ("ies", &["y"]),
+    ("ied", &["y"]),
+    ("ying", &["y", "ie"]),
+    ("es", &["", "e"]),
+    ("s", &[""]),
+    ("ed", &["", "e"]),
+    ("ing", &["", "e"]),
+];
+
+/// Whether a subject's first word is in the imperative mood.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mood {
+    /// An imperative verb, or a word the lexicon does not recognise as a verb
+    Imperative,
+    /// An inflected verb or a denied word, with its imperative form when known
+    NotImperative(Option<String>),
+}
+
+/// The mood of `word`, with the imperative form of an inflected verb, e.g. "added" -> "add".
+///
+/// `allow` words are never flagged; `deny` words are always flagged, with a suggestion
+/// when the lexicon knows their base form.
+pub fn mood(word: &str, allow: &[String], deny: &[String]) -> Mood {
+    let word = word
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase();
+    if allow.iter().any(|w| w.eq_ignore_ascii_case(&word)) {
+        return Mood::Imperative;
+    }
+
+    let denied = deny.iter().any(|w| w.eq_ignore_ascii_case(&word));
+    let verbs: HashSet<&str> = VERBS.iter().copied().collect();
+    if verbs.contains(word.as_str()) && !denied {
+        return Mood::Imperative;
+    }
+
+    if let Some((_, base)) = IRREGULAR.iter().find(|(form, _)| *form == word) {
+        return Mood::NotImperative(Some(base.to_string()));
+    }
+
+    let base = SUFFIXES.iter().find_map(|(suffix, replacements)| {
+        let stem = word.strip_suffix(suffix)?;
+        let mut candidates: Vec<String> = replacements
+            .iter()
+            .map(|replacement| format!("{}{}", stem, replacement))
+            .collect();
+        // "stopped" -> "stop", "running" -> "run"
+        let mut chars = stem.chars().rev();
+        if let (Some(last), Some(previous)) = (chars.next(), chars.next()) {
+            if last == previous {
+                candidates.push(stem[..stem.len() - last.len_utf8()].to_string());
+            }
+        }
+        candidates
+            .into_iter()
+            .find(|candidate| verbs.contains(candidate.as_str()))
+    });
+
+    match base {
+        Some(base) => Mood::NotImperative(Some(base)),
+        None if denied => Mood::NotImperative(None),
+        None => Mood::Imperative,
+    }
+}
+
+/// `subject` with its first word replaced by `base`, keeping the original capitalisation.
+pub fn correct(subject: &str, base: &str) -> String {
+    let start = subject.len() - subject.trim_start().len();
+    let end = subject[start..]
+        .find(char::is_whitespace)
+        .map_or(subject.len(), |i| start + i);
+    let word = &subject[start..end];
+    let word_end = word
+        .rfind(|c: char| c.is_alphanumeric())
+        .map_or(0, |i| i + word[i..].chars().next().unwrap().len_utf8());
+
+    let replacement = if word.starts_with(char::is_uppercase) {
+        let mut chars = base.chars();
+        chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    } else {
+        base.to_string()
+    };
+
+    format!(
+        "{}{}{}{}",
+        &subject[..start],
+        replacement,
+        &word[word_end..],
+        &subject[end..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggestion(word: &str) -> Mood {
+        mood(word, &[], &[])
+    }
+
+    fn inflected(base: &str) -> Mood {
+        Mood::NotImperative(Some(base.to_string()))
+    }
+
+    #[test]
+    fn test_mood() {
+        assert_eq!(suggestion("added"), inflected("add"));
+        assert_eq!(suggestion("updating"), inflected("update"));
+        assert_eq!(suggestion("fixes"), inflected("fix"));
+        assert_eq!(suggestion("applies"), inflected("apply"));
+        assert_eq!(suggestion("stopped"), inflected("stop"));
+        assert_eq!(suggestion("Wrote"), inflected("write"));
+        assert_eq!(suggestion("add"), Mood::Imperative);
+        assert_eq!(suggestion("process"), Mood::Imperative);
+        assert_eq!(suggestion("readme"), Mood::Imperative);
+    }
+
+    #[test]
+    fn test_allow_and_deny() {
+        let words =
+            |values: &[&str]| -> Vec<String> { values.iter().map(|v| v.to_string()).collect() };
+        assert_eq!(mood("added", &words(&["added"]), &[]), Mood::Imperative);
+        assert_eq!(
+            mood("initial", &[], &words(&["initial"])),
+            Mood::NotImperative(None)
+        );
+    }
+
+    #[test]
+    fn test_correct() {
+        assert_eq!(correct("Added caching", "add"), "Add caching");
+        assert_eq!(correct("fixes: crash", "fix"), "fix: crash");
+    }
+}
@@ -0,0 +1,295 @@
+use crate::commit::CommitMessage;
+use crate::config::{Config, EnumEntry, Parser};
+use crate::validator::Validator;
+use std::collections::HashMap;
+
+/// Types, scopes and scope shares below these are treated as one-offs or typos.
+const MIN_USES: usize = 2;
+const MAX_SCOPES: usize = 25;
+
+/// Commit conventions found in a sample of messages.
+#[derive(Debug, Default)]
+pub struct Survey {
+    /// Messages sampled, leaving out merges and the other default ignores
+    pub total: usize,
+    /// Messages that parse as Conventional Commits
+    pub conventional: usize,
+    /// Types by number of uses, most used first
+    pub types: Vec<(String, usize)>,
+    /// Scopes by number of uses, most used first
+    pub scopes: Vec<(String, usize)>,
+    /// Conventional commits with a scope
+    pub scoped: usize,
+    /// Header lengths in characters, shortest first
+    pub header_lengths: Vec<usize>,
+    /// The longest body line of each message with a body, shortest first
+    pub body_line_lengths: Vec<usize>,
+    /// Subjects starting with an uppercase letter
+    pub capitalized: usize,
+    /// Subjects ending with a full stop
+    pub full_stops: usize,
+}
+
+impl Survey {
+    pub fn from_messages(messages: &[String]) -> Self {
+        let defaults = Validator::new(Config::default());
+        let parser = Parser::default();
+        let mut survey = Survey::default();
+        let mut types: HashMap<String, usize> = HashMap::new();
+        let mut scopes: HashMap<String, usize> = HashMap::new();
+
+        for message in messages.iter().filter(|m| !defaults.is_ignored(m)) {
+            survey.total += 1;
+            let msg = CommitMessage::from_str(message);
+            survey.header_lengths.push(msg.header.chars().count());
+            if let Some(longest) = msg
+                .body
+                .iter()
+                .flat_map(|body| body.lines())
+                .map(|line| line.chars().count())
+                .max()
+            {
+                survey.body_line_lengths.push(longest);
+            }
+
+            let Ok(commit) = msg.parse_conventional(&parser) else {
+                continue;
+            };
+            survey.conventional += 1;
+            *types.entry(commit.r#type).or_default() += 1;
+            if !commit.scopes.is_empty() {
+                survey.scoped += 1;
+            }
+            for scope in commit.scopes {
+                *scopes.entry(scope).or_default() += 1;
+            }
+            if commit.subject.starts_with(char::is_uppercase) {
+                survey.capitalized += 1;
+            }
+            if commit.subject.ends_with('.') {
+                survey.full_stops += 1;
+            }
+        }
+
+        survey.types = by_uses(types);
+        survey.scopes = by_uses(scopes);
+        survey.header_lengths.sort_unstable();
+        survey.body_line_lengths.sort_unstable();
+        survey
+    }
+
+    /// A config accepting most of the sampled commits, as the TOML document to write.
+    ///
+    /// Only settings that differ from the defaults are included.
+    pub fn proposal(&self) -> toml::Table {
+        let defaults = Config::default().rules;
+        let mut rules = toml::Table::new();
+
+        // Frequently used types are added to the defaults
+        let mut type_enum = defaults.r#type.r#enum.clone();
+        for (name, _) in self.types.iter().filter(|(_, uses)| *uses >= MIN_USES) {
+            if !type_enum.iter().any(|entry| &entry.name == name) {
+                type_enum.push(EnumEntry::new(name.clone()));
+            }
+        }
+        let mut type_rule = toml::Table::new();
+        if type_enum
+            .iter()
+            .any(|entry| entry.name.to_lowercase() != entry.name)
+        {
+            type_rule.insert("case".into(), "".into());
+        }
+        if type_enum != defaults.r#type.r#enum {
+            let type_enum = toml::Value::try_from(&type_enum).expect("entries serialize");
+            type_rule.insert("enum".into(), type_enum);
+        }
+        if !type_rule.is_empty() {
+            rules.insert("type".into(), type_rule.into());
+        }
+
+        let scope_enum: Vec<&str> = self
+            .scopes
+            .iter()
+            .filter(|(_, uses)| *uses >= MIN_USES)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        let mut scope_rule = toml::Table::new();
+        if !scope_enum.is_empty() && scope_enum.len() <= MAX_SCOPES {
+            let mut scope_enum = scope_enum;
+            scope_enum.sort_unstable();
+            if scope_enum
+                .iter()
+                .any(|scope| scope.to_lowercase() != *scope)
+            {
+                scope_rule.insert("case".into(), "".into());
+            }
+            scope_rule.insert("enum".into(), scope_enum.into());
+        }
+        if self.conventional > 0 && self.scoped * 10 >= self.conventional * 9 {
+            scope_rule.insert("empty".into(), "never".into());
+        }
+        if !scope_rule.is_empty() {
+            rules.insert("scope".into(), scope_rule.into());
+        }
+
+        // Subjects are left alone when neither style clearly dominates
+        if self.conventional > 0 && self.capitalized * 5 >= self.conventional * 4 {
+            rules.insert("subject_case".into(), vec!["sentence-case"].into());
+        } else if self.conventional > 0 && self.capitalized * 5 <= self.conventional {
+            // commitlint's convention, for lower-case subjects
+            let lower = [
+                "never sentence-case",
+                "never start-case",
+                "never pascal-case",
+                "never upper-case",
+            ];
+            rules.insert("subject_case".into(), lower.to_vec().into());
+        }
+        if self.full_stops * 5 > self.conventional {
+            rules.insert("subject_full_stop".into(), "".into());
+        }
+
+        let header = percentile(&self.header_lengths, 95);
+        if header > defaults.header_max_length {
+            rules.insert("header_max_length".into(), round_up(header));
+        }
+        let body = percentile(&self.body_line_lengths, 95);
+        if body > defaults.body_max_line_length {
+            rules.insert("body_max_line_length".into(), round_up(body));
+        }
+
+        let mut document = toml::Table::new();
+        document.insert("rules".into(), rules.into());
+        document
+    }
+
+    /// What the sample looks like, for printing before the compliance rates.
+    pub fn summary(&self) -> String {
+        let uses = |tally: &[(String, usize)]| -> String {
+            let top: Vec<String> = tally
+                .iter()
+                .take(12)
+                .map(|(name, uses)| format!("{} ({})", name, uses))
+                .collect();
+            if top.is_empty() {
+                "none".to_string()
+            } else {
+                top.join(", ")
+            }
+        };
+
+        let mut out = format!(
+            "Sampled {} commits, {} of them Conventional Commits\n",
+            self.total, self.conventional
+        );
+        out.push_str(&format!("Types: {}\n", uses(&self.types)));
+        out.push_str(&format!(
+            "Scopes: {} (on {} of {} commits)\n",
+            uses(&self.scopes),
+            self.scoped,
+            self.conventional
+        ));
+        out.push_str(&format!(
+            "Header length: median {}, 95th percentile {}, longest {}\n",
+            percentile(&self.header_lengths, 50),
+            percentile(&self.header_lengths, 95),
+            self.header_lengths.last().copied().unwrap_or_default()
+        ));
+        out.push_str(&format!(
+            "Subjects: {} capitalized, {} ending with '.'\n",
+            self.capitalized, self.full_stops
+        ));
+        out
+    }
+}
+
+/// How many of `messages` pass under `config`, out of those it does not ignore.
+pub fn compliance(config: &Config, messages: &[String]) -> (usize, usize) {
+    let validator = Validator::new(config.clone());
+    let checked: Vec<&String> = messages
+        .iter()
+        .filter(|m| !validator.is_ignored(m))
+        .collect();
+    let passing = checked
+        .iter()
+        .filter(|m| validator.validate(m).is_ok())
+        .count();
+    (passing, checked.len())
+}
+
+fn by_uses(tally: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut tally: Vec<(String, usize)> = tally.into_iter().collect();
+    tally.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    tally
+}
+
+/// The value `p` percent of `sorted` is at or below.
+fn percentile(sorted: &[usize], p: usize) -> usize {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (sorted.len() * p).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// `length` rounded up to a multiple of ten, as a TOML integer.
+fn round_up(length: usize) -> toml::Value {
+    toml::Value::Integer((length.div_ceil(10) * 10) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_survey_proposal() {
+        let messages: Vec<String> = [
+            "feat(api): Add pagination",
+            "fix(api): Handle empty pages",
+            "fix(cli): Quote paths",
+            "deps(cli): Bump clap",
+            "deps(api): Bump serde",
+            "fxi(cli): Typo in the type",
+            "Update README",
+            "Merge branch 'main' into feature",
+        ]
+        .iter()
+        .map(|m| m.to_string())
+        .collect();
+
+        let survey = Survey::from_messages(&messages);
+        assert_eq!(survey.total, 7);
+        assert_eq!(survey.conventional, 6);
+        assert_eq!(survey.types[0], ("deps".to_string(), 2));
+        assert_eq!(survey.scoped, 6);
+
+        let config: Config = toml::Value::Table(survey.proposal()).try_into().unwrap();
+        let types: Vec<&str> = config
+            .rules
+            .r#type
+            .r#enum
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        assert!(types.contains(&"deps") && !types.contains(&"fxi"));
+        assert_eq!(config.rules.scope.r#enum, ["api".into(), "cli".into()]);
+        assert_eq!(config.rules.subject_case, ["sentence-case"]);
+
+        assert_eq!(compliance(&Config::default(), &messages), (3, 7));
+        assert_eq!(compliance(&config, &messages), (5, 7));
+
+        let lower = Survey::from_messages(&["fix: handle empty pages".to_string()]);
+        let config: Config = toml::Value::Table(lower.proposal()).try_into().unwrap();
+        assert!(config
+            .rules
+            .subject_case
+            .contains(&"never sentence-case".to_string()));
+    }
+
+    #[test]
+    fn test_percentile() {
+        assert_eq!(percentile(&[], 95), 0);
+        assert_eq!(percentile(&[10, 20, 30, 40], 50), 20);
+        assert_eq!(percentile(&[10, 20, 30, 40], 95), 40);
+    }
+}
@@ -0,0 +1,46 @@
+//! Conventional Commits linting behind `cargo commitlint`.
+//!
+//! Load a [`config::Config`] and check messages with [`validator::Validator`]:
+//!
+//! ```no_run
+//! use cargo_commitlint::{config::Config, validator::Validator};
+//!
+//! let config = Config::from_default_locations().unwrap();
+//! for entry in config.rules.r#type.listed() {
+//!     println!("{}: {}", entry.name, entry.description.as_deref().unwrap_or(""));
+//! }
+//!
+//! let validator = Validator::new(config);
+//! assert!(validator.validate("feat(api): add pagination").is_ok());
+//! ```
+
+pub mod commit;
+pub mod config;
+pub mod validator;
+
+// Used by the `cargo-commitlint` binary; not part of the library API
+#[doc(hidden)]
+pub mod doctor;
+#[doc(hidden)]
+pub mod explain;
+#[doc(hidden)]
+pub mod fix;
+#[doc(hidden)]
+pub mod git;
+#[doc(hidden)]
+pub mod hook;
+#[doc(hidden)]
+pub mod init;
+#[doc(hidden)]
+pub mod patch;
+#[doc(hidden)]
+pub mod pr;
+#[doc(hidden)]
+pub mod report;
+
+mod case;
+mod gitmoji;
+mod imperative;
+mod reference;
+mod secrets;
+mod suggest;
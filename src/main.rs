@@ -1,9 +1,7 @@
-mod config;
-mod commit;
-mod hook;
-mod validator;
-
-use clap::{Parser, Subcommand};
+use cargo_commitlint::{
+    commit, config, doctor, explain, fix, git, hook, init, patch, pr, report, validator,
+};
+use clap::{ArgGroup, Parser, Subcommand};
 use std::io::{self, Read};
 use std::process;
 
@@ -18,12 +16,31 @@ struct Cli {
 }
 
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)] // parsed once per run
 enum Commands {
     /// Install git hook for commit message validation
-    Install,
+    Install {
+        /// Replace an existing pre-push hook not written by cargo-commitlint
+        #[arg(long)]
+        force: bool,
+    },
     /// Uninstall git hook
     Uninstall,
     /// Validate a commit message
+    #[command(group(
+        ArgGroup::new("input")
+            .args([
+                "message",
+                "edit",
+                "from",
+                "mbox",
+                "patches",
+                "bundle",
+                "pr_title",
+                "pr_from_env",
+            ])
+            .multiple(false)
+    ))]
     Check {
         /// Commit message to validate (if not provided, reads from stdin)
         #[arg(short, long)]
@@ -31,6 +48,105 @@ enum Commands {
         /// Path to configuration file
         #[arg(short, long)]
         config: Option<std::path::PathBuf>,
+        /// Validate (and autofix) a commit message file, as the commit-msg hook does
+        #[arg(short, long)]
+        edit: Option<std::path::PathBuf>,
+        /// Validate every commit after this revision
+        #[arg(long)]
+        from: Option<String>,
+        /// Last revision of the range to validate (defaults to HEAD)
+        #[arg(long, requires = "from")]
+        to: Option<String>,
+        /// Validate every message in an mbox file (e.g. `git format-patch --stdout` output)
+        #[arg(long)]
+        mbox: Option<std::path::PathBuf>,
+        /// Validate every `*.patch` file in a directory
+        #[arg(long)]
+        patches: Option<std::path::PathBuf>,
+        /// Validate every commit in a `git bundle` file
+        #[arg(long)]
+        bundle: Option<std::path::PathBuf>,
+        /// Validate a pull request title as the header of its squash commit
+        #[arg(long)]
+        pr_title: Option<String>,
+        /// Read the pull request title from GITHUB_EVENT_PATH or CI_MERGE_REQUEST_TITLE
+        #[arg(long)]
+        pr_from_env: bool,
+        /// File containing the pull request description, validated with the body rules
+        #[arg(long)]
+        pr_body_file: Option<std::path::PathBuf>,
+        /// Branch `[[when]]` blocks are matched against (detected for --edit and --from)
+        #[arg(long)]
+        branch: Option<String>,
+        /// Output format (detected from GITHUB_ACTIONS / GITLAB_CI when omitted)
+        #[arg(long, value_enum)]
+        format: Option<report::OutputFormat>,
+        /// Where to write the GitLab Code Quality report
+        #[arg(long, default_value = "gl-code-quality-report.json")]
+        report_file: std::path::PathBuf,
+    },
+    /// Validate a branch name
+    Branch {
+        /// Branch name to validate (defaults to the current branch)
+        name: Option<String>,
+        /// Path to configuration file
+        #[arg(short, long)]
+        config: Option<std::path::PathBuf>,
+        /// Output format (detected from GITHUB_ACTIONS / GITLAB_CI when omitted)
+        #[arg(long, value_enum)]
+        format: Option<report::OutputFormat>,
+        /// Where to write the GitLab Code Quality report
+        #[arg(long, default_value = "gl-code-quality-report.json")]
+        report_file: std::path::PathBuf,
+    },
+    /// Write a commitlint.toml inferred from the existing history
+    Init {
+        /// Number of recent commits to learn from
+        #[arg(short = 'n', long, default_value_t = 200)]
+        commits: usize,
+        /// Where to write the config
+        #[arg(short, long, default_value = "commitlint.toml")]
+        output: std::path::PathBuf,
+        /// Overwrite an existing config
+        #[arg(long)]
+        force: bool,
+        /// Print the config instead of writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List the allowed commit types with their descriptions
+    Types {
+        /// Path to configuration file
+        #[arg(short, long)]
+        config: Option<std::path::PathBuf>,
+        /// Include hidden types
+        #[arg(long)]
+        all: bool,
+    },
+    /// Show the rules and their current settings
+    Rules {
+        #[command(subcommand)]
+        command: RulesCommand,
+    },
+    /// Describe a rule, with examples and how to configure or disable it
+    Explain {
+        /// Rule name, as shown in brackets in error messages (e.g. header-max-length)
+        rule: String,
+        /// Path to configuration file
+        #[arg(short, long)]
+        config: Option<std::path::PathBuf>,
+    },
+    /// Diagnose the git hook and configuration setup
+    Doctor,
+}
+
+#[derive(Subcommand)]
+enum RulesCommand {
+    /// List every rule with its severity and settings under the loaded config
+    List {
+        /// Path to configuration file
+        #[arg(short, long)]
+        config: Option<std::path::PathBuf>,
     },
 }
 
@@ -43,16 +159,107 @@ fn main() {
     let cli = Cli::parse_from(args);
 
     let result = match cli.command {
-        Commands::Install => {
-            hook::HookInstaller::install()
-                .map_err(|e| format!("Failed to install hook: {}", e))
+        Commands::Install { force } => {
+            load_config(None, &config::RunContext::default()).and_then(|config| {
+                hook::HookInstaller::install(config.branch.pre_push, force)
+                    .map_err(|e| format!("Failed to install hook: {}", e))
+            })
         }
         Commands::Uninstall => {
             hook::HookInstaller::uninstall()
                 .map_err(|e| format!("Failed to uninstall hook: {}", e))
         }
-        Commands::Check { message, config } => {
-            validate_commit_message(message, config)
+        Commands::Check {
+            message,
+            config,
+            edit,
+            from,
+            to,
+            mbox,
+            patches,
+            bundle,
+            pr_title,
+            pr_from_env,
+            pr_body_file,
+            branch,
+            format,
+            report_file,
+        } => {
+            // Commits made from the hook or linted as a range belong to a known branch
+            let branch = branch.or_else(|| match (&edit, &from) {
+                (Some(_), _) => git::current_branch().ok(),
+                // A commit id names no branch, as with a CI checkout's detached HEAD
+                (_, Some(_)) => git::branch_name(to.as_deref().unwrap_or("HEAD"))
+                    .or_else(|| git::current_branch().ok()),
+                _ => None,
+            });
+            let hook = edit.as_ref().map(|_| "commit-msg");
+            let context = config::RunContext::detect(branch, hook);
+            let config_path = config.clone();
+
+            let outcomes = if pr_title.is_some() || pr_from_env {
+                read_pull_request(pr_title, pr_body_file)
+                    .and_then(|pr| validate_pull_request(pr, config, &context))
+            } else if pr_body_file.is_some() {
+                Err("--pr-body-file requires --pr-title or --pr-from-env".to_string())
+            } else if let Some(path) = edit {
+                validate_message_file(path, config, &context)
+            } else if let Some(from) = from {
+                validate_range(&from, to.as_deref().unwrap_or("HEAD"), config, &context)
+            } else if let Some(path) = mbox {
+                patch::from_mbox(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|patches| validate_patches(patches, config, &context))
+            } else if let Some(dir) = patches {
+                patch::from_dir(&dir)
+                    .map_err(|e| e.to_string())
+                    .and_then(|patches| validate_patches(patches, config, &context))
+            } else if let Some(path) = bundle {
+                patch::from_bundle(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|patches| validate_patches(patches, config, &context))
+            } else {
+                validate_commit_message(message, config, &context)
+            };
+
+            outcomes.and_then(|outcomes| {
+                let format = format.unwrap_or_else(report::OutputFormat::detect);
+                report::Report::new(outcomes)
+                    .with_config_path(&config_location(config_path))
+                    .emit(format, &report_file)
+            })
+        }
+        Commands::Branch {
+            name,
+            config,
+            format,
+            report_file,
+        } => {
+            let config_path = config.clone();
+            validate_branch(name, config).and_then(|outcomes| {
+                let format = format.unwrap_or_else(report::OutputFormat::detect);
+                report::Report::new(outcomes)
+                    .with_config_path(&config_location(config_path))
+                    .emit(format, &report_file)
+            })
+        }
+        Commands::Init {
+            commits,
+            output,
+            force,
+            dry_run,
+        } => init_config(commits, output, force, dry_run),
+        Commands::Types { config, all } => list_types(config, all),
+        Commands::Rules {
+            command: RulesCommand::List { config },
+        } => load_current_config(config).map(|config| print!("{}", explain::render_list(&config))),
+        Commands::Explain { rule, config } => explain_rule(&rule, config),
+        Commands::Doctor => {
+            if doctor::Doctor::run() {
+                Ok(())
+            } else {
+                Err("Some checks failed".to_string())
+            }
         }
     };
 
@@ -67,18 +274,85 @@ fn main() {
     }
 }
 
-fn validate_commit_message(
-    message: Option<String>,
+/// The config file in use, relative to the working directory where possible.
+fn config_location(config_path: Option<std::path::PathBuf>) -> std::path::PathBuf {
+    let path = config_path
+        .or_else(|| config::Config::find_default_path().ok().flatten())
+        .unwrap_or_else(|| "commitlint.toml".into());
+    match std::env::current_dir() {
+        Ok(dir) => path.strip_prefix(&dir).map(Into::into).unwrap_or(path),
+        Err(_) => path,
+    }
+}
+
+fn load_config(
     config_path: Option<std::path::PathBuf>,
-) -> Result<(), String> {
-    // Load configuration
-    let config = if let Some(path) = config_path {
+    context: &config::RunContext,
+) -> Result<config::Config, String> {
+    resolve_config(&load_base_config(config_path)?, &[], context).map(|(config, _)| config)
+}
+
+fn load_base_config(config_path: Option<std::path::PathBuf>) -> Result<config::Config, String> {
+    if let Some(path) = config_path {
         config::Config::from_file(&path)
-            .map_err(|e| format!("Failed to load config from {}: {}", path.display(), e))?
+            .map_err(|e| format!("Failed to load config from {}: {}", path.display(), e))
     } else {
         config::Config::from_default_locations()
-            .map_err(|e| format!("Failed to load config: {}", e))?
+            .map_err(|e| format!("Failed to load config: {}", e))
+    }
+}
+
+/// The config for a commit touching `files`: nested configs first, then `[[when]]` blocks.
+fn resolve_config(
+    base: &config::Config,
+    files: &[String],
+    context: &config::RunContext,
+) -> Result<(config::Config, config::Applied), String> {
+    let (config, applied) = match git::toplevel() {
+        Ok(root) if !files.is_empty() => base
+            .for_paths(&root, files)
+            .map_err(|e| format!("Failed to load nested config: {}", e))?,
+        _ => (base.clone(), config::Applied::Root),
+    };
+
+    let config = config
+        .for_context(context)
+        .map_err(|e| format!("Failed to apply [[when]] blocks: {}", e))?;
+    Ok((config, applied))
+}
+
+/// Fail commits that span nested configs when `nested.span = "reject"`.
+fn reject_span(
+    config: &config::Config,
+    applied: &config::Applied,
+    result: Result<(), Vec<validator::ValidationError>>,
+) -> Result<(), Vec<validator::ValidationError>> {
+    let config::Applied::Spanning(areas) = applied else {
+        return result;
     };
+    if config.nested.span != config::SpanPolicy::Reject {
+        return result;
+    }
+
+    let mut errors = result.err().unwrap_or_default();
+    let areas: Vec<String> = areas.iter().map(|p| p.display().to_string()).collect();
+    errors.push(validator::ValidationError {
+        rule: "config-span".to_string(),
+        message: format!(
+            "commit touches files covered by different configs ({}); split it up",
+            areas.join(", ")
+        ),
+    });
+    Err(errors)
+}
+
+fn validate_commit_message(
+    message: Option<String>,
+    config_path: Option<std::path::PathBuf>,
+    context: &config::RunContext,
+) -> Result<Vec<report::Outcome>, String> {
+    // Load configuration
+    let config = load_config(config_path, context)?;
 
     // Get commit message
     let commit_msg = if let Some(msg) = message {
@@ -94,17 +368,300 @@ fn validate_commit_message(
 
     // Validate
     let validator = validator::Validator::new(config);
-    match validator.validate(&commit_msg) {
-        Ok(()) => {
-            println!("✓ Commit message is valid");
-            Ok(())
+    let result = validator.validate(&commit_msg);
+    Ok(vec![report::Outcome::new(
+        "Commit message",
+        &validator.redact_secrets(&commit_msg),
+        result,
+    )
+    .with_warnings(validator.warnings(&commit_msg))])
+}
+
+fn validate_message_file(
+    path: std::path::PathBuf,
+    config_path: Option<std::path::PathBuf>,
+    context: &config::RunContext,
+) -> Result<Vec<report::Outcome>, String> {
+    // The commit being created touches the staged files
+    let files = git::staged_files().unwrap_or_default();
+    let (config, applied) = resolve_config(&load_base_config(config_path)?, &files, context)?;
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let comment = match git::comment_char().as_str() {
+        "auto" => commit::detect_comment_char(&raw),
+        comment => comment.to_string(),
+    };
+    let mut commit_msg = commit::cleanup_message(&raw, &comment);
+
+    let validator = validator::Validator::new(config.clone());
+    let mut fix_note = None;
+    if !validator.is_ignored(&commit_msg) {
+        // Fixes go into the file as written, keeping git's comments and the diff
+        // below the scissors line in place
+        let (message, comments) = commit::split_comments(&raw, &comment);
+        if let Some(fixed) = fix::apply(&config, message).map_err(|e| e.to_string())? {
+            fix_note = fixed.note;
+            let fixed = if comments.is_empty() {
+                fixed.message
+            } else {
+                format!("{}\n{}", fixed.message.trim_end(), comments)
+            };
+            std::fs::write(&path, &fixed)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            commit_msg = commit::cleanup_message(&fixed, &comment);
         }
-        Err(errors) => {
-            eprintln!("✗ Commit message validation failed:\n");
-            for error in errors {
-                eprintln!("  - [{}] {}", error.rule, error.message);
-            }
-            Err("Validation failed".to_string())
+    }
+
+    // The commit being created will be authored by the configured identity
+    let result = match git::Author::from_git_config() {
+        Ok(author) => validator.validate_authored(&commit_msg, &author),
+        Err(_) => validator.validate(&commit_msg),
+    };
+    let result = reject_span(&config, &applied, result);
+    Ok(vec![report::Outcome::new(
+        "Commit message",
+        &validator.redact_secrets(&commit_msg),
+        result,
+    )
+    .with_warnings(validator.warnings(&commit_msg))
+    .with_note(join_notes([applied.describe(), fix_note]))])
+}
+
+/// Notes for one report entry, joined into one.
+fn join_notes(notes: [Option<String>; 2]) -> Option<String> {
+    let notes: Vec<String> = notes.into_iter().flatten().collect();
+    (!notes.is_empty()).then(|| notes.join("; "))
+}
+
+fn validate_range(
+    from: &str,
+    to: &str,
+    config_path: Option<std::path::PathBuf>,
+    context: &config::RunContext,
+) -> Result<Vec<report::Outcome>, String> {
+    let base = load_base_config(config_path)?;
+    let commits = git::commits_in_range(from, to).map_err(|e| e.to_string())?;
+
+    commits
+        .iter()
+        .map(|commit| {
+            let files = git::changed_files(&commit.sha).unwrap_or_default();
+            let (config, applied) = resolve_config(&base, &files, context)?;
+            let validator = validator::Validator::new(config.clone());
+            let result = validator.validate_authored(&commit.message, &commit.author);
+            let result = reject_span(&config, &applied, result);
+            let message = validator.redact_secrets(&commit.message);
+            Ok(report::Outcome::new(commit.short_sha(), &message, result)
+                .with_warnings(validator.warnings(&commit.message))
+                .with_note(applied.describe()))
+        })
+        .collect()
+}
+
+fn validate_patches(
+    patches: Vec<patch::Patch>,
+    config_path: Option<std::path::PathBuf>,
+    context: &config::RunContext,
+) -> Result<Vec<report::Outcome>, String> {
+    if patches.is_empty() {
+        return Err("No patches found".to_string());
+    }
+
+    let validator = validator::Validator::new(load_config(config_path, context)?);
+
+    Ok(patches
+        .into_iter()
+        .map(|patch| {
+            let result = validator.validate(&patch.message);
+            let message = validator.redact_secrets(&patch.message);
+            report::Outcome::new(patch.source, &message, result)
+                .with_warnings(validator.warnings(&patch.message))
+        })
+        .collect())
+}
+
+fn validate_branch(
+    name: Option<String>,
+    config_path: Option<std::path::PathBuf>,
+) -> Result<Vec<report::Outcome>, String> {
+    let name = match name {
+        Some(name) => name,
+        None => git::current_branch().map_err(|e| e.to_string())?,
+    };
+    let context = config::RunContext::detect(Some(name.clone()), None);
+    let validator = validator::Validator::new(load_config(config_path, &context)?);
+
+    let result = validator.validate_branch(&name);
+    Ok(vec![report::Outcome::new("Branch", &name, result)])
+}
+
+fn init_config(
+    commits: usize,
+    output: std::path::PathBuf,
+    force: bool,
+    dry_run: bool,
+) -> Result<(), String> {
+    // A config elsewhere in the default locations would be shadowed by the new one
+    let existing = if output.exists() {
+        Some(output.clone())
+    } else {
+        config::Config::find_default_path().map_err(|e| e.to_string())?
+    };
+    if let Some(existing) = existing.filter(|_| !force && !dry_run) {
+        return Err(format!(
+            "{} already exists; pass --force to write {} anyway",
+            existing.display(),
+            output.display()
+        ));
+    }
+
+    let history = git::recent_commits(commits).map_err(|e| e.to_string())?;
+    if history.is_empty() {
+        return Err("No commits to learn from".to_string());
+    }
+    let messages: Vec<String> = history.into_iter().map(|commit| commit.message).collect();
+
+    let survey = init::Survey::from_messages(&messages);
+    let proposal = survey.proposal();
+    let contents =
+        toml::to_string_pretty(&proposal).map_err(|e| format!("Failed to render config: {}", e))?;
+    let config: config::Config = toml::from_str(&contents)
+        .map_err(|e| format!("Failed to read back the proposed config: {}", e))?;
+
+    let rate = |(passing, checked): (usize, usize)| match checked {
+        0 => "n/a".to_string(),
+        _ => format!("{}% ({}/{})", passing * 100 / checked, passing, checked),
+    };
+    print!("{}", survey.summary());
+    println!(
+        "Passing: {} with the defaults, {} with the proposed config",
+        rate(init::compliance(&config::Config::default(), &messages)),
+        rate(init::compliance(&config, &messages))
+    );
+
+    let contents = format!(
+        "# Inferred by `cargo commitlint init` from the last {} commits\n\n{}",
+        messages.len(),
+        contents
+    );
+    if dry_run {
+        print!("\n{}", contents);
+        return Ok(());
+    }
+    std::fs::write(&output, contents)
+        .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+    println!("Wrote {}", output.display());
+    Ok(())
+}
+
+/// The config for the current branch, for commands that describe rather than validate.
+fn load_current_config(config_path: Option<std::path::PathBuf>) -> Result<config::Config, String> {
+    let context = config::RunContext::detect(git::current_branch().ok(), None);
+    load_config(config_path, &context)
+}
+
+fn list_types(config_path: Option<std::path::PathBuf>, all: bool) -> Result<(), String> {
+    let config = load_current_config(config_path)?;
+    let types = &config.rules.r#type;
+
+    if types.r#enum.is_empty() {
+        println!("Any type is allowed");
+        return Ok(());
+    }
+
+    let entries: Vec<&config::EnumEntry> = if all {
+        types.r#enum.iter().collect()
+    } else {
+        types.listed().collect()
+    };
+    let width = entries.iter().map(|entry| entry.name.chars().count()).max();
+    let width = width.unwrap_or_default();
+    for entry in entries {
+        let mut line = format!("{:width$}", entry.name);
+        if let Some(ref emoji) = entry.emoji {
+            line.push_str(&format!("  {}", emoji));
         }
+        if let Some(ref description) = entry.description {
+            line.push_str(&format!("  {}", description));
+        }
+        if let Some(ref section) = entry.changelog_section {
+            line.push_str(&format!(" [{}]", section));
+        }
+        let hidden = if entry.hidden { " (hidden)" } else { "" };
+        println!("{}{}", line.trim_end(), hidden);
+    }
+
+    let mut aliases: Vec<_> = types.aliases.iter().collect();
+    aliases.sort();
+    for (alias, target) in aliases {
+        println!("{} → {} (alias)", alias, target);
+    }
+
+    let mut deprecated: Vec<_> = types.deprecated.iter().collect();
+    deprecated.sort();
+    for (name, replacement) in deprecated {
+        if replacement.is_empty() {
+            println!("{} (deprecated)", name);
+        } else {
+            println!("{} (deprecated, use {})", name, replacement);
+        }
+    }
+
+    Ok(())
+}
+
+fn explain_rule(rule: &str, config_path: Option<std::path::PathBuf>) -> Result<(), String> {
+    let Some(doc) = explain::find(rule) else {
+        return Err(match explain::suggest(rule) {
+            Some(suggestion) => format!("Unknown rule '{}', did you mean '{}'?", rule, suggestion),
+            None => format!("Unknown rule '{}'; run `cargo commitlint rules list`", rule),
+        });
+    };
+
+    let config = load_current_config(config_path)?;
+    print!("{}", explain::render(doc, &config));
+    Ok(())
+}
+
+fn read_pull_request(
+    title: Option<String>,
+    body_file: Option<std::path::PathBuf>,
+) -> Result<pr::PullRequest, String> {
+    let mut pr = match title {
+        Some(title) => pr::PullRequest { title, body: None },
+        None => pr::PullRequest::from_env().map_err(|e| e.to_string())?,
+    };
+
+    if let Some(path) = body_file {
+        let body = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        pr.body = Some(body);
+    }
+
+    Ok(pr)
+}
+
+fn validate_pull_request(
+    pr: pr::PullRequest,
+    config_path: Option<std::path::PathBuf>,
+    context: &config::RunContext,
+) -> Result<Vec<report::Outcome>, String> {
+    let validator = validator::Validator::new(load_config(config_path, context)?);
+    let result = validator.validate_pull_request(&pr.title, pr.body.as_deref());
+    let mut outcomes = vec![report::Outcome::new(
+        "Pull request title",
+        &validator.redact_secrets(&pr.title),
+        result,
+    )
+    .with_warnings(validator.warnings(&pr.title))];
+
+    if let Some(body) = pr.body.as_deref().filter(|body| !body.trim().is_empty()) {
+        let result = validator.validate_pull_request_body(&pr.title, body);
+        outcomes.push(report::Outcome::new(
+            "Pull request body",
+            &validator.redact_secrets(body.trim()),
+            result,
+        ));
     }
+    Ok(outcomes)
 }
@@ -0,0 +1,271 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+
+/// A commit message extracted from an emailed patch.
+#[derive(Debug, Clone)]
+pub struct Patch {
+    pub source: String,
+    pub message: String,
+}
+
+/// Extract every message from an mbox file, e.g. `git format-patch --stdout` output.
+pub fn from_mbox(path: &Path) -> Result<Vec<Patch>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read mbox {}", path.display()))?;
+
+    Ok(mbox_patches(&content, &path.display().to_string()))
+}
+
+/// The patches in an mbox, numbered `name [n/total]`.
+fn mbox_patches(content: &str, name: &str) -> Vec<Patch> {
+    // Emails without a Subject carry no commit message and are not counted
+    let messages: Vec<String> = split_mbox(content)
+        .iter()
+        .filter_map(|email| parse_email(email))
+        .collect();
+    let total = messages.len();
+
+    messages
+        .into_iter()
+        .enumerate()
+        .map(|(i, message)| Patch {
+            source: format!("{} [{}/{}]", name, i + 1, total),
+            message,
+        })
+        .collect()
+}
+
+/// Extract the message of every commit in a `git bundle` file, oldest first.
+pub fn from_bundle(path: &Path) -> Result<Vec<Patch>> {
+    Ok(crate::git::commits_in_bundle(path)?
+        .into_iter()
+        .map(|commit| Patch {
+            source: format!("{} {}", path.display(), commit.short_sha()),
+            message: commit.message,
+        })
+        .collect())
+}
+
+/// Extract the message from each `*.patch` file in `dir`, in file name order.
+pub fn from_dir(dir: &Path) -> Result<Vec<Patch>> {
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read patch directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "patch"))
+        .collect();
+    paths.sort();
+
+    let mut patches = Vec::new();
+    for path in paths {
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read patch {}", path.display()))?;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        for email in split_mbox(&content) {
+            if let Some(message) = parse_email(&email) {
+                patches.push(Patch {
+                    source: name.clone(),
+                    message,
+                });
+            }
+        }
+    }
+
+    Ok(patches)
+}
+
+/// Split an mbox into individual emails on `From ` separator lines.
+fn split_mbox(content: &str) -> Vec<String> {
+    let separator = Regex::new(r"^From \S+ ").unwrap();
+    let mut emails = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut previous_blank = true;
+
+    for line in content.lines() {
+        if previous_blank && separator.is_match(line) {
+            if !current.is_empty() {
+                emails.push(current.join("\n"));
+            }
+            current = Vec::new();
+        } else {
+            current.push(line);
+        }
+        previous_blank = line.trim().is_empty();
+    }
+
+    if current.iter().any(|line| !line.trim().is_empty()) {
+        emails.push(current.join("\n"));
+    }
+
+    emails
+}
+
+/// Build a commit message from an email's `Subject:` and the body up to the `---` separator.
+fn parse_email(email: &str) -> Option<String> {
+    let mut lines = email.lines();
+    let mut subject: Option<String> = None;
+    let mut in_subject = false;
+
+    // Headers run until the first blank line; folded headers continue with whitespace
+    for line in lines.by_ref() {
+        if line.is_empty() {
+            break;
+        }
+
+        if in_subject && line.starts_with([' ', '\t']) {
+            if let Some(ref mut subject) = subject {
+                subject.push(' ');
+                subject.push_str(line.trim());
+            }
+            continue;
+        }
+
+        in_subject = false;
+        if let Some(value) = line.strip_prefix("Subject:") {
+            subject = Some(value.trim().to_string());
+            in_subject = true;
+        }
+    }
+
+    let subject = decode_header(&subject?);
+    let header = strip_patch_prefix(&subject).to_string();
+
+    let mut body_lines = Vec::new();
+    for line in lines {
+        // The diffstat follows `---`; an empty commit goes straight to the `-- ` signature
+        if line == "---" || line == "-- " {
+            break;
+        }
+        // mbox escapes body lines beginning with "From "
+        body_lines.push(
+            line.strip_prefix('>')
+                .filter(|rest| rest.starts_with("From "))
+                .unwrap_or(line),
+        );
+    }
+
+    let body = body_lines.join("\n");
+    let body = body.trim();
+
+    if body.is_empty() {
+        Some(header)
+    } else {
+        Some(format!("{}\n\n{}", header, body))
+    }
+}
+
+/// Strip leading `[PATCH n/m]`-style tags, as `git am` does.
+pub fn strip_patch_prefix(subject: &str) -> &str {
+    let prefix = Regex::new(r"^(?:\s*\[[^\]]*\])+\s*").unwrap();
+    match prefix.find(subject) {
+        Some(m) => &subject[m.end()..],
+        None => subject,
+    }
+}
+
+/// Decode RFC 2047 `=?charset?q?...?=` words, which `git format-patch` uses for non-ASCII subjects.
+fn decode_header(value: &str) -> String {
+    let word = Regex::new(r"=\?[^?]+\?[qQ]\?([^?]*)\?=").unwrap();
+    if !word.is_match(value) {
+        return value.to_string();
+    }
+
+    // Whitespace between adjacent encoded words is not significant
+    let joined = Regex::new(r"\?=\s+=\?").unwrap().replace_all(value, "?==?");
+
+    let mut bytes = Vec::new();
+    let mut last = 0;
+    for caps in word.captures_iter(&joined) {
+        let whole = caps.get(0).unwrap();
+        bytes.extend_from_slice(joined[last..whole.start()].as_bytes());
+        bytes.extend(decode_q(&caps[1]));
+        last = whole.end();
+    }
+    bytes.extend_from_slice(joined[last..].as_bytes());
+
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+fn decode_q(text: &str) -> Vec<u8> {
+    let raw = text.as_bytes();
+    let mut out = Vec::with_capacity(raw.len());
+    let mut i = 0;
+
+    while i < raw.len() {
+        match raw[i] {
+            b'_' => out.push(b' '),
+            b'=' => match text
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(byte) => {
+                    out.push(byte);
+                    i += 2;
+                }
+                None => out.push(b'='),
+            },
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERIES: &str = "From 1111111111111111111111111111111111111111 Mon Sep 17 00:00:00 2001
+From: Jane Doe <jane@example.com>
+Subject: [PATCH 1/2] feat(parser): add mbox
+ support
+
+Parse patches sent by email.
+---
+ src/patch.rs | 1 +
+ 1 file changed, 1 insertion(+)
+
+From 2222222222222222222222222222222222222222 Mon Sep 17 00:00:00 2001
+From: Jane Doe <jane@example.com>
+Subject: [PATCH v2 2/2] =?UTF-8?q?fix:=20handle=20caf=C3=A9?=
+
+---
+ src/patch.rs | 1 +
+";
+
+    #[test]
+    fn test_split_and_parse_series() {
+        let emails = split_mbox(SERIES);
+        assert_eq!(emails.len(), 2);
+        assert_eq!(
+            parse_email(&emails[0]).unwrap(),
+            "feat(parser): add mbox support\n\nParse patches sent by email."
+        );
+        assert_eq!(parse_email(&emails[1]).unwrap(), "fix: handle café");
+    }
+
+    #[test]
+    fn test_mbox_patches_count_only_messages() {
+        let content = format!(
+            "From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001\n\
+             From: Jane Doe <jane@example.com>\n\nNo subject.\n\n{}",
+            SERIES
+        );
+        let patches = mbox_patches(&content, "series.mbox");
+        let sources: Vec<&str> = patches.iter().map(|p| p.source.as_str()).collect();
+        assert_eq!(sources, ["series.mbox [1/2]", "series.mbox [2/2]"]);
+    }
+
+    #[test]
+    fn test_strip_patch_prefix() {
+        assert_eq!(strip_patch_prefix("[PATCH 3/5] fix: typo"), "fix: typo");
+        assert_eq!(strip_patch_prefix("[RFC] [PATCH v2] feat: x"), "feat: x");
+        assert_eq!(strip_patch_prefix("feat: no prefix"), "feat: no prefix");
+    }
+}
@@ -0,0 +1,77 @@
+use anyhow::{Context, Result};
+use std::env;
+use std::fs;
+
+/// A pull/merge request title, and optionally its description.
+#[derive(Debug, Clone)]
+pub struct PullRequest {
+    pub title: String,
+    pub body: Option<String>,
+}
+
+impl PullRequest {
+    /// Read the pull request title and description from the CI environment.
+    ///
+    /// GitHub Actions exposes the event payload at `GITHUB_EVENT_PATH`; GitLab CI sets
+    /// `CI_MERGE_REQUEST_TITLE` and `CI_MERGE_REQUEST_DESCRIPTION` for merge request
+    /// pipelines.
+    pub fn from_env() -> Result<Self> {
+        if let Ok(path) = env::var("GITHUB_EVENT_PATH") {
+            let payload = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read GitHub event payload {}", path))?;
+            return Self::from_github_event(&payload);
+        }
+
+        if let Ok(title) = env::var("CI_MERGE_REQUEST_TITLE") {
+            let body = env::var("CI_MERGE_REQUEST_DESCRIPTION").ok();
+            return Ok(Self { title, body });
+        }
+
+        anyhow::bail!(
+            "No pull request found: set GITHUB_EVENT_PATH (GitHub Actions) or CI_MERGE_REQUEST_TITLE (GitLab CI)"
+        )
+    }
+
+    pub fn from_github_event(payload: &str) -> Result<Self> {
+        let event: serde_json::Value =
+            serde_json::from_str(payload).context("Failed to parse GitHub event payload")?;
+
+        let title = event
+            .pointer("/pull_request/title")
+            .and_then(|title| title.as_str())
+            .ok_or_else(|| {
+                anyhow::anyhow!("GitHub event payload does not describe a pull request")
+            })?;
+
+        // An empty description is sent as `null`
+        let body = event
+            .pointer("/pull_request/body")
+            .and_then(|body| body.as_str())
+            .map(str::to_string);
+
+        Ok(Self {
+            title: title.to_string(),
+            body,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_github_event() {
+        let payload =
+            r#"{"action": "edited", "pull_request": {"title": "feat: add x", "body": null}}"#;
+        let pr = PullRequest::from_github_event(payload).unwrap();
+        assert_eq!(pr.title, "feat: add x");
+        assert_eq!(pr.body, None);
+
+        let payload = r#"{"pull_request": {"title": "feat: add x", "body": "Adds x."}}"#;
+        let pr = PullRequest::from_github_event(payload).unwrap();
+        assert_eq!(pr.body.as_deref(), Some("Adds x."));
+
+        assert!(PullRequest::from_github_event(r#"{"ref": "refs/heads/main"}"#).is_err());
+    }
+}
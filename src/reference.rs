@@ -0,0 +1,134 @@
+use regex::Regex;
+
+/// An issue reference such as `#12`, `GH-12` or `org/repo#12`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    /// The action keyword preceding the reference, e.g. `Closes`
+    pub action: Option<String>,
+    /// `owner/repo` for cross-repository references
+    pub repository: Option<String>,
+    pub prefix: String,
+    pub issue: String,
+    pub raw: String,
+}
+
+fn reference_pattern(prefixes: &[String]) -> Option<String> {
+    if prefixes.is_empty() {
+        return None;
+    }
+
+    let prefixes: Vec<String> = prefixes.iter().map(|p| regex::escape(p)).collect();
+    Some(format!(
+        r"(?P<repository>[\w.-]+/[\w.-]+)?(?P<prefix>{})(?P<issue>\d+)",
+        prefixes.join("|")
+    ))
+}
+
+/// Find every reference in `text`, attaching the action keyword that introduces it, if any.
+pub fn parse_references(text: &str, prefixes: &[String], actions: &[String]) -> Vec<Reference> {
+    let Some(pattern) = reference_pattern(prefixes) else {
+        return Vec::new();
+    };
+    let reference = Regex::new(&format!(r"(?:^|[^\w/#-]){}\b", pattern)).unwrap();
+    let separator = Regex::new(r"(?i)^(?:\s*(?:,|&|\band\b))*\s*$").unwrap();
+
+    let mut references = Vec::new();
+
+    for line in text.lines() {
+        let mut last_end = 0;
+        let mut action: Option<String> = None;
+
+        for caps in reference.captures_iter(line) {
+            let start = caps
+                .name("repository")
+                .unwrap_or_else(|| caps.name("prefix").unwrap());
+            let gap = &line[last_end..start.start()];
+
+            // "Closes #1, #2 and #3" applies the action to every reference in the list
+            action = match last_word(gap).filter(|word| is_action(word, actions)) {
+                Some(word) => Some(word.to_string()),
+                None if separator.is_match(gap) => action,
+                None => None,
+            };
+
+            let end = caps.name("issue").unwrap().end();
+            references.push(Reference {
+                action: action.clone(),
+                repository: caps.name("repository").map(|m| m.as_str().to_string()),
+                prefix: caps["prefix"].to_string(),
+                issue: caps["issue"].to_string(),
+                raw: line[start.start()..end].to_string(),
+            });
+            last_end = end;
+        }
+    }
+
+    references
+}
+
+/// Items in an action trailer (e.g. `Closes: #1, oops`) that are not valid references.
+pub fn invalid_references(value: &str, prefixes: &[String]) -> Vec<String> {
+    let Some(pattern) = reference_pattern(prefixes) else {
+        return Vec::new();
+    };
+    let reference = Regex::new(&format!("^{}$", pattern)).unwrap();
+    let separator = Regex::new(r"(?i)\s*(?:,|\band\b|&)\s*").unwrap();
+
+    separator
+        .split(value.trim())
+        .map(str::trim)
+        .filter(|item| !item.is_empty() && !reference.is_match(item))
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn is_action(word: &str, actions: &[String]) -> bool {
+    actions
+        .iter()
+        .any(|action| action.eq_ignore_ascii_case(word))
+}
+
+fn last_word(text: &str) -> Option<&str> {
+    text.trim_end()
+        .trim_end_matches(':')
+        .split_whitespace()
+        .next_back()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_references_with_actions() {
+        let prefixes = vec!["#".to_string(), "PROJ-".to_string()];
+        let actions = vec!["closes".to_string(), "refs".to_string()];
+        let refs = parse_references(
+            "Closes #1, #2 and org/repo#3\nsee PROJ-9",
+            &prefixes,
+            &actions,
+        );
+
+        let summary: Vec<(Option<&str>, &str)> = refs
+            .iter()
+            .map(|r| (r.action.as_deref(), r.raw.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Some("Closes"), "#1"),
+                (Some("Closes"), "#2"),
+                (Some("Closes"), "org/repo#3"),
+                (None, "PROJ-9"),
+            ]
+        );
+        assert_eq!(refs[2].repository.as_deref(), Some("org/repo"));
+    }
+
+    #[test]
+    fn test_invalid_references() {
+        let prefixes = vec!["#".to_string(), "GH-".to_string()];
+        assert!(invalid_references("#12, GH-4", &prefixes).is_empty());
+        assert_eq!(invalid_references("#12, ticket 4", &prefixes), ["ticket 4"]);
+    }
+}
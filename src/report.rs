@@ -0,0 +1,339 @@
+use crate::validator::ValidationError;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// How validation results are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable output
+    Text,
+    /// GitHub Actions workflow commands (`::error ...`)
+    Github,
+    /// GitLab Code Quality JSON report
+    Gitlab,
+}
+
+impl OutputFormat {
+    /// Pick the format for the CI system we are running in, if any.
+    pub fn detect() -> Self {
+        if env::var("GITHUB_ACTIONS").is_ok_and(|value| value == "true") {
+            OutputFormat::Github
+        } else if env::var_os("GITLAB_CI").is_some() {
+            OutputFormat::Gitlab
+        } else {
+            OutputFormat::Text
+        }
+    }
+}
+
+/// The validation result for one message.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// What was validated, e.g. "Commit message" or a patch file name
+    pub source: String,
+    pub header: String,
+    pub errors: Vec<ValidationError>,
+    /// Problems that do not fail validation, e.g. a deprecated type
+    pub warnings: Vec<ValidationError>,
+    /// Extra context printed with the result, e.g. which nested config applied
+    pub note: Option<String>,
+}
+
+impl Outcome {
+    pub fn new(
+        source: impl Into<String>,
+        message: &str,
+        result: Result<(), Vec<ValidationError>>,
+    ) -> Self {
+        Self {
+            source: source.into(),
+            header: message.lines().next().unwrap_or_default().to_string(),
+            errors: result.err().unwrap_or_default(),
+            warnings: Vec::new(),
+            note: None,
+        }
+    }
+
+    pub fn with_warnings(mut self, warnings: Vec<ValidationError>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    pub fn with_note(mut self, note: Option<String>) -> Self {
+        self.note = note;
+        self
+    }
+
+    pub fn passed(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+#[derive(Serialize)]
+struct CodeQualityIssue {
+    description: String,
+    check_name: String,
+    fingerprint: String,
+    severity: &'static str,
+    location: CodeQualityLocation,
+}
+
+#[derive(Serialize)]
+struct CodeQualityLocation {
+    path: String,
+    lines: CodeQualityLines,
+}
+
+#[derive(Serialize)]
+struct CodeQualityLines {
+    begin: usize,
+}
+
+pub struct Report {
+    outcomes: Vec<Outcome>,
+    /// The file Code Quality findings are placed on
+    config_path: String,
+}
+
+impl Report {
+    pub fn new(outcomes: Vec<Outcome>) -> Self {
+        Self {
+            outcomes,
+            config_path: "commitlint.toml".to_string(),
+        }
+    }
+
+    /// Place Code Quality findings on the config file at `path`, relative to the
+    /// repository. Commit messages are not files GitLab could point at.
+    pub fn with_config_path(mut self, path: &Path) -> Self {
+        self.config_path = path.display().to_string();
+        self
+    }
+
+    /// Write the report in `format`. Fails if any message failed validation.
+    pub fn emit(&self, format: OutputFormat, report_file: &Path) -> Result<(), String> {
+        match format {
+            OutputFormat::Text => {}
+            OutputFormat::Github => print!("{}", self.github_annotations()),
+            OutputFormat::Gitlab => {
+                fs::write(report_file, self.gitlab_code_quality())
+                    .map_err(|e| format!("Failed to write {}: {}", report_file.display(), e))?;
+            }
+        }
+
+        self.print_text()
+    }
+
+    fn print_text(&self) -> Result<(), String> {
+        if let [outcome] = self.outcomes.as_slice() {
+            if outcome.passed() {
+                println!("✓ {} is valid", outcome.source);
+                if let Some(note) = &outcome.note {
+                    println!("  ({})", note);
+                }
+                print_warnings(outcome);
+                return Ok(());
+            }
+
+            eprintln!("✗ {} validation failed:\n", outcome.source);
+            if let Some(note) = &outcome.note {
+                eprintln!("  ({})", note);
+            }
+            for error in &outcome.errors {
+                eprintln!("  - [{}] {}", error.rule, error.message);
+            }
+            print_warnings(outcome);
+            return Err("Validation failed".to_string());
+        }
+
+        for outcome in &self.outcomes {
+            if outcome.passed() {
+                println!("✓ {}: {}", outcome.source, outcome.header);
+                if let Some(note) = &outcome.note {
+                    println!("  ({})", note);
+                }
+                print_warnings(outcome);
+            } else {
+                eprintln!("✗ {}: {}", outcome.source, outcome.header);
+                if let Some(note) = &outcome.note {
+                    eprintln!("  ({})", note);
+                }
+                for error in &outcome.errors {
+                    eprintln!("  - [{}] {}", error.rule, error.message);
+                }
+                print_warnings(outcome);
+            }
+        }
+
+        let failed = self.outcomes.iter().filter(|o| !o.passed()).count();
+        if failed == 0 {
+            println!("\n✓ All {} messages are valid", self.outcomes.len());
+            Ok(())
+        } else {
+            Err(format!(
+                "{} of {} messages failed validation",
+                failed,
+                self.outcomes.len()
+            ))
+        }
+    }
+
+    /// One `::error` workflow command per validation error, and `::warning` per warning.
+    pub fn github_annotations(&self) -> String {
+        let mut out = String::new();
+
+        for outcome in &self.outcomes {
+            let errors = outcome.errors.iter().map(|error| ("error", error));
+            let warnings = outcome.warnings.iter().map(|warning| ("warning", warning));
+            for (command, error) in errors.chain(warnings) {
+                out.push_str(&format!(
+                    "::{} title={}::{}\n",
+                    command,
+                    escape_github_property(&error.rule),
+                    escape_github_data(&format!("{}: {}", outcome.source, error.message))
+                ));
+            }
+        }
+
+        out
+    }
+
+    /// A GitLab Code Quality report listing every validation error and warning.
+    pub fn gitlab_code_quality(&self) -> String {
+        let issues: Vec<CodeQualityIssue> = self
+            .outcomes
+            .iter()
+            .flat_map(|outcome| {
+                let errors = outcome.errors.iter().map(|error| ("major", error));
+                let warnings = outcome.warnings.iter().map(|warning| ("minor", warning));
+                errors
+                    .chain(warnings)
+                    .map(move |(severity, error)| CodeQualityIssue {
+                        description: format!(
+                            "{} ({}): {}",
+                            outcome.source, outcome.header, error.message
+                        ),
+                        check_name: error.rule.clone(),
+                        fingerprint: fingerprint(&[&outcome.source, &error.rule, &error.message]),
+                        severity,
+                        location: CodeQualityLocation {
+                            path: self.config_path.clone(),
+                            lines: CodeQualityLines { begin: 1 },
+                        },
+                    })
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&issues).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+fn print_warnings(outcome: &Outcome) {
+    for warning in &outcome.warnings {
+        eprintln!("  ⚠ [{}] {}", warning.rule, warning.message);
+    }
+}
+
+/// A 64-bit FNV-1a hash of `parts`, stable across runs and Rust versions so GitLab can
+/// match findings between pipelines.
+fn fingerprint(parts: &[&str]) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut hash = OFFSET_BASIS;
+    for (i, part) in parts.iter().enumerate() {
+        // A separator byte keeps ["ab", "c"] and ["a", "bc"] apart
+        let separator = (i > 0).then_some(0u8);
+        for byte in separator.into_iter().chain(part.bytes()) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+fn escape_github_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_github_property(value: &str) -> String {
+    escape_github_data(value)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failing_outcome() -> Outcome {
+        Outcome::new(
+            "Commit message",
+            "feature: add x",
+            Err(vec![ValidationError {
+                rule: "type-enum".to_string(),
+                message: "type must be one of [feat, fix]".to_string(),
+            }]),
+        )
+    }
+
+    #[test]
+    fn test_github_annotations() {
+        let report = Report::new(vec![failing_outcome()]);
+        assert_eq!(
+            report.github_annotations(),
+            "::error title=type-enum::Commit message: type must be one of [feat, fix]\n"
+        );
+    }
+
+    #[test]
+    fn test_warnings() {
+        let outcome = Outcome::new("Commit message", "chore: x", Ok(())).with_warnings(vec![
+            ValidationError {
+                rule: "type-deprecated".to_string(),
+                message: "type 'chore' is deprecated, use 'build' instead".to_string(),
+            },
+        ]);
+        assert!(outcome.passed());
+
+        let report = Report::new(vec![outcome]);
+        assert_eq!(
+            report.github_annotations(),
+            "::warning title=type-deprecated::Commit message: \
+             type 'chore' is deprecated, use 'build' instead\n"
+        );
+        let json: serde_json::Value = serde_json::from_str(&report.gitlab_code_quality()).unwrap();
+        assert_eq!(json[0]["severity"], "minor");
+    }
+
+    #[test]
+    fn test_gitlab_code_quality() {
+        let report = Report::new(vec![failing_outcome()]);
+        let json: serde_json::Value = serde_json::from_str(&report.gitlab_code_quality()).unwrap();
+        assert_eq!(json[0]["check_name"], "type-enum");
+        assert_eq!(json[0]["severity"], "major");
+        assert_eq!(json[0]["location"]["path"], "commitlint.toml");
+        assert_eq!(
+            json[0]["description"],
+            "Commit message (feature: add x): type must be one of [feat, fix]"
+        );
+        assert_eq!(json[0]["location"]["lines"]["begin"], 1);
+
+        let report = Report::new(vec![failing_outcome()])
+            .with_config_path(Path::new(".cargo/commitlint.toml"));
+        let json: serde_json::Value = serde_json::from_str(&report.gitlab_code_quality()).unwrap();
+        assert_eq!(json[0]["location"]["path"], ".cargo/commitlint.toml");
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        assert_eq!(fingerprint(&["a"]), "af63dc4c8601ec8c");
+        assert_ne!(fingerprint(&["ab", "c"]), fingerprint(&["a", "bc"]));
+    }
+}
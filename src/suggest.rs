@@ -0,0 +1,74 @@
+/// The candidate `word` is most likely a typo of, e.g. `fix` for `fxi` or `feat` for
+/// `feature`.
+///
+/// Candidates within two edits (counting a transposition as one) qualify, as do
+/// candidates `word` starts with or that start with `word`; the closest wins.
+pub fn closest<'a, S: AsRef<str>>(word: &str, candidates: &'a [S]) -> Option<&'a str> {
+    let word = word.to_lowercase();
+    let length = word.chars().count();
+
+    candidates
+        .iter()
+        .map(AsRef::as_ref)
+        .filter(|candidate| !candidate.contains('*'))
+        .filter_map(|candidate| {
+            let lowercase = candidate.to_lowercase();
+            let distance = edit_distance(&word, &lowercase);
+            let shortest = length.min(lowercase.chars().count());
+            let typo = distance <= 2 && distance < length;
+            let prefix =
+                shortest >= 3 && (word.starts_with(&lowercase) || lowercase.starts_with(&word));
+            (typo || prefix).then_some((distance, candidate))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions and adjacent
+/// transpositions each cost one.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closest() {
+        let types: Vec<String> = ["feat", "fix", "docs", "refactor", "test"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+
+        assert_eq!(closest("fxi", &types), Some("fix"));
+        assert_eq!(closest("feature", &types), Some("feat"));
+        assert_eq!(closest("Doc", &types), Some("docs"));
+        assert_eq!(closest("refactr", &types), Some("refactor"));
+        assert_eq!(closest("release", &types), None);
+        assert_eq!(closest("x", &types), None);
+    }
+}
@@ -0,0 +1,108 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(["-c", "user.name=Jane", "-c", "user.email=jane@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {:?} failed", args);
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+fn commitlint(dir: &Path, args: &[&str], env: &[(&str, &str)]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_cargo-commitlint"));
+    command.args(args).current_dir(dir);
+    for var in [
+        "GITHUB_HEAD_REF",
+        "GITHUB_REF_NAME",
+        "CI_COMMIT_REF_NAME",
+        "CI",
+    ] {
+        command.env_remove(var);
+    }
+    command.envs(env.iter().copied()).output().unwrap()
+}
+
+#[test]
+fn test_range_branch_falls_back_to_ci_env() {
+    let repo = tempfile::tempdir().unwrap();
+    let dir = repo.path();
+    git(dir, &["init", "-q"]);
+    std::fs::write(
+        dir.join("commitlint.toml"),
+        "[[when]]\nbranch = [\"release/*\"]\n\n[when.rules]\nheader_max_length = 10\n",
+    )
+    .unwrap();
+    for message in ["chore: initial", "feat: add a long enough header"] {
+        git(dir, &["commit", "-q", "--allow-empty", "-m", message]);
+    }
+    // CI checks out the commit being built, leaving HEAD detached
+    let head = git(dir, &["rev-parse", "HEAD"]);
+    git(dir, &["checkout", "-q", "--detach", &head]);
+
+    let range = ["check", "--from", "HEAD~1", "--to", head.as_str()];
+    assert!(commitlint(dir, &range, &[]).status.success());
+
+    let output = commitlint(dir, &range, &[("GITHUB_HEAD_REF", "release/1.0")]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("header-max-length"), "{}", stderr);
+}
+
+#[test]
+fn test_init_refuses_to_shadow_an_existing_config() {
+    let repo = tempfile::tempdir().unwrap();
+    let dir = repo.path();
+    git(dir, &["init", "-q"]);
+    git(
+        dir,
+        &["commit", "-q", "--allow-empty", "-m", "feat: add export"],
+    );
+    std::fs::create_dir(dir.join(".cargo")).unwrap();
+    std::fs::write(dir.join(".cargo/commitlint.toml"), "").unwrap();
+
+    let output = commitlint(dir, &["init"], &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("commitlint.toml already exists"),
+        "{}",
+        stderr
+    );
+    assert!(!dir.join("commitlint.toml").exists());
+
+    assert!(commitlint(dir, &["init", "--force"], &[]).status.success());
+    assert!(dir.join("commitlint.toml").exists());
+}
+
+#[test]
+fn test_bundle_leaves_the_repository_untouched() {
+    let repo = tempfile::tempdir().unwrap();
+    let dir = repo.path();
+    git(dir, &["init", "-q"]);
+    for message in ["feat: add export", "fix: handle empty input"] {
+        git(dir, &["commit", "-q", "--allow-empty", "-m", message]);
+    }
+    let bundle = dir.join("all.bundle");
+    git(
+        dir,
+        &["bundle", "create", "-q", bundle.to_str().unwrap(), "HEAD"],
+    );
+    git(
+        dir,
+        &["bundle", "create", "-q", "tip.bundle", "HEAD~1..HEAD"],
+    );
+    let objects = git(dir, &["count-objects", "-v"]);
+
+    let tip = commitlint(dir, &["check", "--bundle", "tip.bundle"], &[]);
+    assert!(tip.status.success());
+    assert_eq!(git(dir, &["count-objects", "-v"]), objects);
+
+    // Outside any repository too
+    let elsewhere = tempfile::tempdir().unwrap();
+    let args = ["check", "--bundle", bundle.to_str().unwrap()];
+    assert!(commitlint(elsewhere.path(), &args, &[]).status.success());
+}